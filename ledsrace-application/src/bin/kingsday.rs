@@ -3,6 +3,10 @@
 #![no_main]
 
 use embassy_executor::Spawner;
+use embassy_futures::{
+    join::join,
+    select::{select, Either},
+};
 use embassy_sync::{
     blocking_mutex::raw::NoopRawMutex,
     channel::{Channel, Receiver, Sender},
@@ -13,7 +17,7 @@ use esp_hal::{
     analog::adc::{Adc, AdcConfig, AdcPin, Attenuation},
     dma::{DmaRxBuf, DmaTxBuf},
     dma_buffers,
-    gpio::{GpioPin, Input, InputConfig, Pull},
+    gpio::{Input, InputConfig, Pull},
     spi::{
         master::{Config, Spi, SpiDmaBus},
         Mode,
@@ -22,24 +26,39 @@ use esp_hal::{
     timer::timg::TimerGroup,
     Async, Blocking,
 };
-use heapless08::Vec;
-
-use ledsrace_core::{animation::*, Circuit, Color};
-use once_cell::sync::Lazy;
+use ledsrace_core::{
+    animation::*,
+    clock::{Clock, SystemClock},
+    dither::TemporalDither,
+    driver::{hd108, HD108},
+    power::{PowerLimiter, PowerModel},
+    show::load_show,
+    stats::FrameStats,
+    Circuit, Color,
+};
 use static_cell::StaticCell;
 
-use ledsrace::hd108::HD108;
-use ledsrace::zandvoort::Zandvoort;
+use ledsrace::{
+    board::{BoardCircuit, TemperaturePin, LED_COUNT, LED_CURRENT_BUDGET_MA, LED_REMAP},
+    board_pins,
+};
+
+const LED_BUFFER_SIZE: usize = hd108::required_buffer_size::<LED_COUNT>();
+
+/// Holding the button at least this long toggles temporal dithering instead of skipping ahead
+const LONG_PRESS: Duration = Duration::from_millis(1000);
 
-#[cfg(feature = "board20x20")]
-const LED_COUNT: usize = 216;
+/// Compiled show played by the queue
+const KINGSDAY_SHOW: &[u8] = include_bytes!("../../shows/kingsday.bin");
 
-const LED_BUFFER_SIZE: usize = ledsrace::hd108::required_buffer_size::<LED_COUNT>();
+/// How often frame timing stats are printed
+const STATS_INTERVAL: Duration = Duration::from_secs(5);
 
 type AdcCal = esp_hal::analog::adc::AdcCalLine<esp_hal::peripherals::ADC1>;
 
 enum Message {
     ButtonPressed,
+    ButtonLongPressed,
 }
 
 static SIGNAL_CHANNEL: StaticCell<Channel<NoopRawMutex, Message, 1>> = StaticCell::new();
@@ -53,11 +72,7 @@ async fn main(spawner: Spawner) {
     let timg0 = TimerGroup::new(peripherals.TIMG0);
     esp_hal_embassy::init(timg0.timer0);
 
-    let analog_pin = peripherals.GPIO1;
-    let sclk = peripherals.GPIO6;
-    let miso = peripherals.GPIO8;
-    let mosi = peripherals.GPIO7;
-    let cs = peripherals.GPIO9;
+    let pins = board_pins!(peripherals);
 
     let dma_channel = peripherals.DMA_CH0;
 
@@ -72,10 +87,10 @@ async fn main(spawner: Spawner) {
             .with_mode(Mode::_0),
     )
     .unwrap()
-    .with_sck(sclk)
-    .with_mosi(mosi)
-    .with_miso(miso)
-    .with_cs(cs)
+    .with_sck(pins.sclk)
+    .with_mosi(pins.mosi)
+    .with_miso(pins.miso)
+    .with_cs(pins.cs)
     .with_dma(dma_channel)
     .with_buffers(dma_rx_buf, dma_tx_buf)
     .into_async();
@@ -90,17 +105,15 @@ async fn main(spawner: Spawner) {
         .spawn(led_task2(hd108, signal_channel.receiver()))
         .unwrap();
 
-    let button_pin = Input::new(
-        peripherals.GPIO10,
-        InputConfig::default().with_pull(Pull::Up),
-    );
+    let button_pin = Input::new(pins.button, InputConfig::default().with_pull(Pull::Up));
 
     spawner
         .spawn(button_task(button_pin, signal_channel.sender()))
         .unwrap();
 
     let mut adc1_config = AdcConfig::new();
-    let adc1_pin = adc1_config.enable_pin_with_cal::<_, AdcCal>(analog_pin, Attenuation::_11dB);
+    let adc1_pin =
+        adc1_config.enable_pin_with_cal::<_, AdcCal>(pins.temperature, Attenuation::_11dB);
     let adc1 = Adc::new(peripherals.ADC1, adc1_config);
 
     spawner.spawn(temperature_task(adc1, adc1_pin)).unwrap();
@@ -111,85 +124,88 @@ async fn led_task2(
     mut hd108: HD108<SpiDmaBus<'static, Async>, LED_COUNT>,
     receiver: Receiver<'static, NoopRawMutex, Message, 1>,
 ) {
-    const OFF: Color = Color(0, 0, 0);
-    const PURPLE: Color = Color(101, 10, 50);
-    const GREEN: Color = Color(30, 150, 1);
-    const YELLOW: Color = Color(160, 106, 2);
-    const ORANGE: Color = Color(255, 20, 0);
-    const RED: Color = Color(255, 0, 0);
-    const WHITE: Color = Color(255, 255, 255);
-    const BRIGHT_YELLOW: Color = Color(255, 105, 0);
-
-    // static OVERDUEL: StaticCell<OvertakeDuel> = StaticCell::new();
-    // static OVERDUEL_ANIM: StaticCell<Animations> = StaticCell::new();
-    static OVERDUEL_ANIM: Lazy<Animations> =
-        Lazy::new(|| Animations::OvertakeDuel(OvertakeDuel::new(LED_COUNT)));
-
-    static LIGHTNING_SPRINT: Lazy<Animations> = Lazy::new(|| {
-        let anim = LightningSprint::new(ORANGE); // Dutch orange for Kingsday
-        Animations::LightningSprint(anim)
-    });
-
-    static CIRCUIT_PULSE: Lazy<Animations> = Lazy::new(|| {
-        Animations::CircuitPulse(CircuitPulse::new(
-            30.0, // Speed: 30 units per second
-            10.0, // Pulse width: 10 units
-            [ORANGE, BRIGHT_YELLOW, RED],
-            Some(Duration::from_millis(5000)), // New pulse every 2 seconds
-            true,                              // Randomize position
-        ))
-    });
-
-    static DUTCH_FLAG: Lazy<Animations> = Lazy::new(|| Animations::DutchFlag(DutchFlag::new()));
-
-    static STATIC_COLOR: Lazy<Animations> =
-        Lazy::new(|| Animations::Static(StaticColor::new(ORANGE)));
-
-    static GROWING_TRAIL: Lazy<Animations> = Lazy::new(|| {
-        let anim = GrowingTrail::new(ORANGE, 60.0);
-        Animations::GrowingTrail(anim)
-    });
-
-    let mut queue = AnimationQueue::new(Duration::from_secs(5));
-
-    // queue.add_animation(&*LIGHTNING_SPRINT);
-    queue.add_animation(&*GROWING_TRAIL);
-    queue.add_animation(&*CIRCUIT_PULSE);
-    queue.add_animation(&*STATIC_COLOR);
-    queue.add_animation(&*OVERDUEL_ANIM);
-    queue.add_animation(&*DUTCH_FLAG);
+    // Edit shows/kingsday.show and compile it with ledsrace-show to change the show
+    let mut queue: AnimationQueue = match load_show(KINGSDAY_SHOW, LED_COUNT) {
+        Ok(queue) => queue,
+        Err(err) => {
+            esp_println::println!("Invalid show, {}", err);
+            let mut queue = AnimationQueue::new(Duration::from_secs(5));
+            queue.add_animation(Animations::Static(StaticColor::new(Color::ORANGE)));
+            queue
+        }
+    };
 
     // receiver.receive().await;
 
     let frame_duration = Duration::from_millis(20);
-    let start = Instant::now();
     let mut ticker = Ticker::every(frame_duration);
+    let mut stats = FrameStats::new();
+    let mut last_report = Instant::now();
 
-    let mut zandvoort: Zandvoort<LED_COUNT> = Zandvoort::new();
+    // The queue's time, host tests drive it with a `ManualClock` instead
+    let clock = SystemClock;
+    let mut circuit = BoardCircuit::new();
+    let mut limiter = PowerLimiter::new(PowerModel::HD108_DEFAULT_GAIN, LED_CURRENT_BUDGET_MA);
+    let mut dither: TemporalDither<LED_COUNT> = TemporalDither::new(true);
 
     loop {
         // Check for button press
-        if let Ok(Message::ButtonPressed) = receiver.try_receive() {
-            queue.next_animation();
-            let _ = hd108.set_off().await;
+        match receiver.try_receive() {
+            Ok(Message::ButtonPressed) => {
+                queue.next_animation();
+                dither.clear();
+            }
+            Ok(Message::ButtonLongPressed) => {
+                dither.set_enabled(!dither.is_enabled());
+                esp_println::println!("Temporal dithering enabled: {}", dither.is_enabled());
+            }
+            Err(_) => {}
         }
 
         ticker.next().await;
-        let _elapsed = start.elapsed();
-
-        // Get current LED states from animation
-        queue.render(&mut zandvoort, Instant::now());
-
-        let led_states = zandvoort.led_buffer().get_colors();
-
-        // Convert to LED updates
-        let mut updates = Vec::<_, LED_COUNT>::new();
-        for (i, (color, _)) in led_states.iter().enumerate() {
-            updates.push((i, color.0, color.1, color.2)).unwrap();
+        let frame_start = Instant::now();
+
+        // Send the previous frame while rendering the next one. The transfer is polled first so
+        // the DMA is running before the render blocks the executor.
+        let ((transfer_result, transfer_time), render_time) = join(
+            async {
+                let start = Instant::now();
+                let result = hd108.flush().await;
+                (result, start.elapsed())
+            },
+            async {
+                let start = Instant::now();
+
+                // Get current LED states from animation
+                queue.render(&mut circuit, clock.now());
+
+                // Keep bright frames within what the USB port can supply, dithering the scaled
+                // result so dim fades don't step
+                let buffer = circuit.led_buffer();
+                let brightness =
+                    limiter.update(buffer.get_colors().iter().map(|(color, _)| *color));
+                dither.apply(buffer, brightness);
+                start.elapsed()
+            },
+        )
+        .await;
+        transfer_result.unwrap();
+
+        // Animations render in track order, the board decides the order of the chain
+        let colors: [Color; LED_COUNT] =
+            core::array::from_fn(|i| circuit.led_buffer().get_colors()[i].0);
+        hd108.encode(LED_REMAP.apply(colors));
+
+        stats.record(
+            render_time,
+            transfer_time,
+            frame_start.elapsed() > frame_duration,
+        );
+        if last_report.elapsed() >= STATS_INTERVAL {
+            esp_println::println!("{}", stats);
+            stats.reset();
+            last_report = Instant::now();
         }
-
-        // Update LEDs
-        hd108.set_leds(&updates).await.unwrap();
     }
 }
 
@@ -201,8 +217,17 @@ async fn button_task(
     loop {
         // Wait for a button press
         button_pin.wait_for_falling_edge().await;
-        esp_println::println!("Button pressed");
-        sender.send(Message::ButtonPressed).await;
+        match select(button_pin.wait_for_rising_edge(), Timer::after(LONG_PRESS)).await {
+            Either::First(_) => {
+                esp_println::println!("Button pressed");
+                sender.send(Message::ButtonPressed).await;
+            }
+            Either::Second(_) => {
+                esp_println::println!("Button long pressed");
+                sender.send(Message::ButtonLongPressed).await;
+                button_pin.wait_for_rising_edge().await;
+            }
+        }
         Timer::after(Duration::from_millis(400)).await; // Debounce delay
     }
 }
@@ -219,7 +244,7 @@ fn convert_voltage_to_temperature(pin_mv: u16) -> f32 {
 #[embassy_executor::task]
 async fn temperature_task(
     mut adc1: Adc<'static, esp_hal::peripherals::ADC1, Blocking>,
-    mut adc1_pin: AdcPin<GpioPin<1>, esp_hal::peripherals::ADC1, AdcCal>,
+    mut adc1_pin: AdcPin<TemperaturePin, esp_hal::peripherals::ADC1, AdcCal>,
 ) {
     loop {
         // Non-blocking read of ADC value
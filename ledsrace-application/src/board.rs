@@ -0,0 +1,110 @@
+//! The board the firmware is built for, picked with a cargo feature. A board decides the circuit
+//! layout, and with it the LED count and sectors, the order the LEDs are chained in, and which
+//! pins the LEDs, button and temperature sensor are wired to.
+//!
+//! Build for another board with e.g. `cargo build --no-default-features --features board30x20`.
+
+#[cfg(all(feature = "board20x20", feature = "board30x20"))]
+compile_error!("Enable only one board feature");
+
+#[cfg(not(any(feature = "board20x20", feature = "board30x20")))]
+compile_error!("Enable a board feature, e.g. `board20x20`");
+
+#[cfg(feature = "board20x20")]
+pub use board20x20::*;
+#[cfg(feature = "board30x20")]
+pub use board30x20::*;
+
+/// Current the LEDs may draw together, leaving headroom for the ESP32 on a 500 mA USB port
+pub const LED_CURRENT_BUDGET_MA: f32 = 400.0;
+
+/// The pins the firmware uses, each board declares its own and takes them with
+/// `board_pins!(peripherals)`
+pub struct Pins {
+    pub sclk: SclkPin,
+    pub mosi: MosiPin,
+    pub miso: MisoPin,
+    pub cs: CsPin,
+    pub button: ButtonPin,
+    pub temperature: TemperaturePin,
+}
+
+/// Circuit Zandvoort on the 20x20 board
+#[cfg(feature = "board20x20")]
+mod board20x20 {
+    use esp_hal::gpio::GpioPin;
+    use ledsrace_core::{circuits::zandvoort::Zandvoort, remap::LedRemap};
+
+    pub use ledsrace_core::circuits::zandvoort::LED_COUNT;
+
+    pub type BoardCircuit = Zandvoort<LED_COUNT>;
+
+    /// The LEDs are chained in track order
+    pub static LED_REMAP: LedRemap<LED_COUNT> = LedRemap::identity();
+
+    /// The 2024 Dutch Grand Prix, with positions scaled to this board
+    pub const RACE: Option<&[u8]> = Some(include_bytes!(
+        "../../ledsrace-core/races/zandvoort_2024_20x20_5hz.bin"
+    ));
+
+    // The LEDs on SPI at GPIO6-9, the button at GPIO10 and the temperature sensor at GPIO1
+    pub type SclkPin = GpioPin<6>;
+    pub type MosiPin = GpioPin<7>;
+    pub type MisoPin = GpioPin<8>;
+    pub type CsPin = GpioPin<9>;
+    pub type ButtonPin = GpioPin<10>;
+    pub type TemperaturePin = GpioPin<1>;
+
+    #[macro_export]
+    macro_rules! board_pins {
+        ($peripherals:ident) => {
+            $crate::board::Pins {
+                sclk: $peripherals.GPIO6,
+                mosi: $peripherals.GPIO7,
+                miso: $peripherals.GPIO8,
+                cs: $peripherals.GPIO9,
+                button: $peripherals.GPIO10,
+                temperature: $peripherals.GPIO1,
+            }
+        };
+    }
+}
+
+/// Circuit de Spa-Francorchamps on the 30x20 board
+#[cfg(feature = "board30x20")]
+mod board30x20 {
+    use esp_hal::gpio::GpioPin;
+    use ledsrace_core::{circuits::spa::Spa, remap::LedRemap};
+
+    pub use ledsrace_core::circuits::spa::LED_COUNT;
+
+    pub type BoardCircuit = Spa<LED_COUNT>;
+
+    /// The LEDs are chained in track order
+    pub static LED_REMAP: LedRemap<LED_COUNT> = LedRemap::identity();
+
+    /// No race has been recorded for this board yet
+    pub const RACE: Option<&[u8]> = None;
+
+    // The LEDs on SPI at GPIO6-9, the button at GPIO10 and the temperature sensor at GPIO1
+    pub type SclkPin = GpioPin<6>;
+    pub type MosiPin = GpioPin<7>;
+    pub type MisoPin = GpioPin<8>;
+    pub type CsPin = GpioPin<9>;
+    pub type ButtonPin = GpioPin<10>;
+    pub type TemperaturePin = GpioPin<1>;
+
+    #[macro_export]
+    macro_rules! board_pins {
+        ($peripherals:ident) => {
+            $crate::board::Pins {
+                sclk: $peripherals.GPIO6,
+                mosi: $peripherals.GPIO7,
+                miso: $peripherals.GPIO8,
+                cs: $peripherals.GPIO9,
+                button: $peripherals.GPIO10,
+                temperature: $peripherals.GPIO1,
+            }
+        };
+    }
+}
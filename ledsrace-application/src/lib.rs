@@ -1,5 +1,4 @@
 #![no_std]
 
+pub mod board;
 pub mod driver_info;
-pub mod hd108;
-pub mod zandvoort;
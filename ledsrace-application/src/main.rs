@@ -7,13 +7,13 @@ use embassy_sync::{
     blocking_mutex::raw::NoopRawMutex,
     channel::{Channel, Receiver, Sender},
 };
-use embassy_time::{Duration, Instant, Ticker, Timer};
+use embassy_time::{Duration, Ticker, Timer};
 use esp_backtrace as _;
 use esp_hal::{
     analog::adc::{Adc, AdcConfig, AdcPin, Attenuation},
     dma::{DmaRxBuf, DmaTxBuf},
     dma_buffers,
-    gpio::{GpioPin, Input, InputConfig, Pull},
+    gpio::{Input, InputConfig, Pull},
     spi::{
         master::{Config, Spi, SpiDmaBus},
         Mode,
@@ -24,15 +24,23 @@ use esp_hal::{
 };
 use esp_println::println;
 
-use ledsrace_core::data_frame::UpdateFrame;
+use ledsrace_core::{
+    animation::{Animation, RacePlayback},
+    clock::{FrameClock, SystemClock},
+    diagnostics::diagnostic_steps,
+    driver::{hd108, LedDriver, HD108},
+    power::{PowerLimiter, PowerModel},
+    Circuit, Color,
+};
 use static_cell::StaticCell;
 
-use ledsrace::{driver_info::DRIVERS_2024, hd108::HD108};
-
-#[cfg(feature = "board20x20")]
-const LED_COUNT: usize = 216;
+use ledsrace::{
+    board::{BoardCircuit, TemperaturePin, LED_COUNT, LED_CURRENT_BUDGET_MA, LED_REMAP, RACE},
+    board_pins,
+    driver_info::DRIVERS_2024,
+};
 
-const LED_BUFFER_SIZE: usize = ledsrace::hd108::required_buffer_size::<LED_COUNT>();
+const LED_BUFFER_SIZE: usize = hd108::required_buffer_size::<LED_COUNT>();
 
 type AdcCal = esp_hal::analog::adc::AdcCalLine<esp_hal::peripherals::ADC1>;
 
@@ -51,11 +59,7 @@ async fn main(spawner: Spawner) {
     let timg0 = TimerGroup::new(peripherals.TIMG0);
     esp_hal_embassy::init(timg0.timer0);
 
-    let analog_pin = peripherals.GPIO1;
-    let sclk = peripherals.GPIO6;
-    let miso = peripherals.GPIO8;
-    let mosi = peripherals.GPIO7;
-    let cs = peripherals.GPIO9;
+    let pins = board_pins!(peripherals);
 
     let dma_channel = peripherals.DMA_CH0;
 
@@ -70,10 +74,10 @@ async fn main(spawner: Spawner) {
             .with_mode(Mode::_0),
     )
     .unwrap()
-    .with_sck(sclk)
-    .with_mosi(mosi)
-    .with_miso(miso)
-    .with_cs(cs)
+    .with_sck(pins.sclk)
+    .with_mosi(pins.mosi)
+    .with_miso(pins.miso)
+    .with_cs(pins.cs)
     .with_dma(dma_channel)
     .with_buffers(dma_rx_buf, dma_tx_buf)
     .into_async();
@@ -83,22 +87,23 @@ async fn main(spawner: Spawner) {
     let hd108: HD108<_, LED_COUNT> = HD108::new(spi, LED_BUF.init([0; LED_BUFFER_SIZE]));
     let signal_channel = SIGNAL_CHANNEL.init(Channel::new());
 
+    let button_pin = Input::new(pins.button, InputConfig::default().with_pull(Pull::Up));
+
+    // Holding the button at boot runs the LED diagnostics before the race
+    let diagnostics = button_pin.is_low();
+
     // Spawn the led task with the receiver
     spawner
-        .spawn(led_task(hd108, signal_channel.receiver()))
+        .spawn(led_task(hd108, signal_channel.receiver(), diagnostics))
         .unwrap();
 
-    let button_pin = Input::new(
-        peripherals.GPIO10,
-        InputConfig::default().with_pull(Pull::Up),
-    );
-
     spawner
         .spawn(button_task(button_pin, signal_channel.sender()))
         .unwrap();
 
     let mut adc1_config = AdcConfig::new();
-    let adc1_pin = adc1_config.enable_pin_with_cal::<_, AdcCal>(analog_pin, Attenuation::_11dB);
+    let adc1_pin =
+        adc1_config.enable_pin_with_cal::<_, AdcCal>(pins.temperature, Attenuation::_11dB);
     let adc1 = Adc::new(peripherals.ADC1, adc1_config);
 
     spawner.spawn(temperature_task(adc1, adc1_pin)).unwrap();
@@ -130,7 +135,7 @@ fn convert_voltage_to_temperature(pin_mv: u16) -> f32 {
 #[embassy_executor::task]
 async fn temperature_task(
     mut adc1: Adc<'static, esp_hal::peripherals::ADC1, Blocking>,
-    mut adc1_pin: AdcPin<GpioPin<1>, esp_hal::peripherals::ADC1, AdcCal>,
+    mut adc1_pin: AdcPin<TemperaturePin, esp_hal::peripherals::ADC1, AdcCal>,
 ) {
     loop {
         // Non-blocking read of ADC value
@@ -169,122 +174,95 @@ async fn temperature_task(
 async fn led_task(
     mut hd108: HD108<SpiDmaBus<'static, Async>, LED_COUNT>,
     receiver: Receiver<'static, NoopRawMutex, Message, 1>,
+    diagnostics: bool,
 ) {
-    // Define the brightness levels
-    let low_brightness = 10; // Low brightness for background LEDs
-
-    // Start the train animation immediately
-    let high_brightness = 255;
-    let train_length = 15;
-    let colors = [
-        (high_brightness, 0, 0),
-        (high_brightness, 0, 0),
-        (high_brightness, 0, 0),
-        (high_brightness, 0, 0),
-        (high_brightness, 0, 0),
-        (0, 0, high_brightness),
-        (0, 0, high_brightness),
-        (0, 0, high_brightness),
-        (0, 0, high_brightness),
-        (0, 0, high_brightness),
-        (0, high_brightness, 0),
-        (0, high_brightness, 0),
-        (0, high_brightness, 0),
-        (0, high_brightness, 0),
-        (0, high_brightness, 0),
-    ];
-
-    let mut iteration_count = 0;
-
-    while iteration_count < 1 {
-        for i in 1..=LED_COUNT {
-            let mut led_updates: heapless08::Vec<(usize, u8, u8, u8), LED_COUNT> =
-                heapless08::Vec::new();
-            // Set all LEDs to low brightness
-            for j in 1..=LED_COUNT {
-                led_updates
-                    .push((j, low_brightness, low_brightness, low_brightness))
-                    .unwrap();
-            }
-
-            // Update the train LEDs with high brightness colors
-            for j in 0..train_length {
-                let pos = (i + j) % LED_COUNT;
-                let color = colors[j];
-                led_updates[pos] = (pos, color.0, color.1, color.2);
-            }
+    let mut circuit = BoardCircuit::new();
 
-            hd108.set_leds(&led_updates).await.unwrap();
-            Timer::after(Duration::from_millis(20)).await;
-        }
-        iteration_count += 1;
+    if diagnostics {
+        run_diagnostics(&mut hd108, &mut circuit).await;
     }
 
-    println!("Startup animation complete...");
-
-    // Set all leds off
-    hd108.set_off().await.unwrap();
-
     loop {
         // Wait for the start message
         receiver.receive().await;
 
+        let Some(race) = RACE else {
+            println!("No race recorded for {}", circuit.name());
+            continue;
+        };
+
         println!("Starting race...");
-        let mut ticker = Ticker::every(Duration::from_millis(50));
-
-        // Start deserialization in chunks
-        let data_bin = include_bytes!("zandvoort_2024_20x20_5hz.bin");
-        let mut remaining_data = &data_bin[..];
-
-        while !remaining_data.is_empty() {
-            // Attempt to deserialize a single frame from the data using `try_from_bytes`
-            match UpdateFrame::try_from_bytes(remaining_data) {
-                Ok(frame) => {
-                    // Move the remaining_data pointer forward by the size of the serialized frame
-                    let frame_size = UpdateFrame::SERIALIZED_SIZE;
-                    remaining_data = &remaining_data[frame_size..];
-
-                    // Prepare LED updates
-                    let mut led_updates: heapless08::Vec<(usize, u8, u8, u8), 216> =
-                        heapless08::Vec::new();
-                    for driver_data in &frame.frame {
-                        if let Some(driver) = DRIVERS_2024
-                            .iter()
-                            .find(|d| d.number == driver_data.driver_number as u32)
-                        {
-                            led_updates
-                                .push((
-                                    driver_data.led_num as usize,
-                                    driver.color.0,
-                                    driver.color.1,
-                                    driver.color.2,
-                                ))
-                                .unwrap();
-                        }
-                    }
-
-                    // Set the LEDs for this frame
-                    if let Err(err) = hd108.set_leds(&led_updates).await {
-                        println!("Failed to set LEDs: {:?}", err);
-                    }
-
-                    // Wait for the next frame update
-                    ticker.next().await;
-                }
-                Err(_) => {
-                    println!("Failed to deserialize frame");
-                    break;
-                }
+        let frame_duration = Duration::from_millis(50);
+        let mut ticker = Ticker::every(frame_duration);
+
+        let mut playback = RacePlayback::new(race, frame_duration, driver_color);
+
+        let mut frames = FrameClock::new(SystemClock);
+        while !playback.is_finished() {
+            playback.update(&circuit, frames.tick());
+
+            circuit.led_buffer().clear();
+            playback.render(&mut circuit);
+
+            // Set the LEDs for this frame
+            let colors: [Color; LED_COUNT] =
+                core::array::from_fn(|i| circuit.led_buffer().get_colors()[i].0);
+            if let Err(err) = hd108.write(LED_REMAP.apply(colors)).await {
+                println!("Failed to set LEDs: {:?}", err);
             }
 
             // Check if a stop message was received
             if receiver.try_receive().is_ok() {
-                hd108.set_off().await.unwrap();
                 break;
             }
+
+            // Wait for the next frame update
+            ticker.next().await;
         }
 
         // Ensure LEDs are turned off at the end
         hd108.set_off().await.unwrap();
     }
 }
+
+/// Team color of a driver, cars of unknown drivers are not shown
+fn driver_color(driver_number: u8) -> Option<Color> {
+    DRIVERS_2024
+        .iter()
+        .find(|d| d.number == driver_number as u32)
+        .map(|driver| Color(driver.color.0, driver.color.1, driver.color.2))
+}
+
+/// Assembly test pattern, to check that every LED works and the chain order is correct
+async fn run_diagnostics(
+    hd108: &mut HD108<SpiDmaBus<'static, Async>, LED_COUNT>,
+    circuit: &mut BoardCircuit,
+) {
+    println!("Running LED diagnostics...");
+
+    // Full color fills and the high gains of the sweep would draw more than the USB port can
+    // supply, dim them to the budget
+    let mut limiter = PowerLimiter::new(PowerModel::HD108_DEFAULT_GAIN, LED_CURRENT_BUDGET_MA);
+    limiter.set_release(1.0);
+
+    for step in diagnostic_steps(LED_COUNT) {
+        println!("{}", step);
+
+        step.render(circuit);
+        limiter.set_model(step.power_model());
+        limiter.apply(circuit.led_buffer());
+        let colors: [Color; LED_COUNT] =
+            core::array::from_fn(|i| circuit.led_buffer().get_colors()[i].0);
+        hd108.set_brightness(step.brightness());
+        if let Err(err) = hd108.write(LED_REMAP.apply(colors)).await {
+            println!("Failed to set LEDs: {:?}", err);
+        }
+
+        Timer::after(step.hold()).await;
+    }
+
+    println!("LED diagnostics complete");
+
+    hd108.set_brightness(0);
+    hd108.set_off().await.unwrap();
+}
@@ -0,0 +1,85 @@
+//! Float against fixed-point math for one frame of 216 LEDs.
+//!
+//! ```bash
+//! cargo bench --bench math
+//! ```
+//!
+//! The host has an FPU, while on the ESP32-C3 every float operation goes through soft-float. The
+//! numbers here only show the difference on the host.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use embassy_time::Duration;
+use ledsrace_core::{
+    animation::{Animation, MexicanWave, UnicornRainbow, WaveAnimation},
+    fixed,
+    zandvoort::Zandvoort,
+    Circuit, Color,
+};
+
+const LED_COUNT: usize = 216;
+
+fn sin(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sin per frame");
+    group.bench_function("libm::sinf", |b| {
+        b.iter(|| {
+            (0..LED_COUNT)
+                .map(|i| {
+                    libm::sinf(black_box(i as f32 / LED_COUNT as f32) * core::f32::consts::TAU)
+                })
+                .sum::<f32>()
+        })
+    });
+    group.bench_function("fixed::sin", |b| {
+        b.iter(|| {
+            (0..LED_COUNT)
+                .map(|i| fixed::sin(black_box((i * 65536 / LED_COUNT) as u16)))
+                .sum::<i32>()
+        })
+    });
+    group.finish();
+}
+
+fn sqrt(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sqrt per frame");
+    group.bench_function("libm::sqrtf", |b| {
+        b.iter(|| {
+            (0..LED_COUNT)
+                .map(|i| libm::sqrtf(black_box((i * i * 7) as f32)))
+                .sum::<f32>()
+        })
+    });
+    group.bench_function("fixed::isqrt", |b| {
+        b.iter(|| {
+            (0..LED_COUNT)
+                .map(|i| fixed::isqrt(black_box((i * i * 7) as u32)))
+                .sum::<u32>()
+        })
+    });
+    group.finish();
+}
+
+fn frame<A: Animation>(c: &mut Criterion, name: &str, mut animation: A) {
+    let mut zandvoort: Zandvoort<LED_COUNT> = Zandvoort::new();
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            animation.update(&zandvoort, Duration::from_millis(16));
+            animation.render(&mut zandvoort);
+            black_box(zandvoort.led_buffer().get_colors());
+        })
+    });
+}
+
+fn animations(c: &mut Criterion) {
+    frame(
+        c,
+        "frame/mexican wave",
+        MexicanWave::new(0.5, 20.0, Color::ORANGE),
+    );
+    frame(c, "frame/unicorn rainbow", UnicornRainbow::new(0.2, 60.0));
+    frame(c, "frame/wave", WaveAnimation::new(0.5, 30.0));
+}
+
+criterion_group!(benches, sin, sqrt, animations);
+criterion_main!(benches);
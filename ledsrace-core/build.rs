@@ -0,0 +1,228 @@
+//! Turns the circuit descriptors in `circuits/` into `Circuit` implementations, see
+//! `src/circuits.rs`.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+const CIRCUITS_DIR: &str = "circuits";
+
+struct Descriptor {
+    name: String,
+    seed: u32,
+    sectors: Vec<usize>,
+    positions: Vec<(f32, f32)>,
+    start_finish: usize,
+    /// Name, first and last LED
+    corners: Vec<(String, usize, usize)>,
+    /// Detection LED, first and last LED of the activation zone
+    drs_zones: Vec<(usize, usize, usize)>,
+    /// Entry and exit LED
+    pit_lane: Option<(usize, usize)>,
+}
+
+fn parse(source: &str) -> Result<Descriptor, (usize, String)> {
+    let mut name = None;
+    let mut seed = None;
+    let mut sectors = Vec::new();
+    let mut positions = Vec::new();
+    let mut start_finish = 0;
+    let mut corners = Vec::new();
+    let mut drs_zones = Vec::new();
+    let mut pit_lane = None;
+    // LED indices with the line they are on, checked once the LED count is known
+    let mut indices = Vec::new();
+
+    for (line_index, line) in source.lines().enumerate() {
+        let line_number = line_index + 1;
+        let error = |message: String| (line_number, message);
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let (keyword, rest) = split_word(line);
+        if keyword.is_empty() {
+            continue;
+        }
+        let number = |value: &str| {
+            value
+                .parse::<f32>()
+                .map_err(|_| error(format!("Not a number: {value}")))
+        };
+        let mut index = |value: &str| -> Result<usize, (usize, String)> {
+            let index: usize = value
+                .parse()
+                .map_err(|_| error(format!("Not an LED index: {value}")))?;
+            indices.push((line_number, index));
+            Ok(index)
+        };
+
+        match keyword {
+            "name" if !rest.is_empty() => name = Some(rest.to_string()),
+            "seed" => {
+                let value = rest
+                    .parse()
+                    .map_err(|_| error(format!("Not a seed: {rest}")))?;
+                seed = Some(value);
+            }
+            "sector" => sectors.push(index(rest)?),
+            "start" => start_finish = index(rest)?,
+            "corner" => {
+                let (first, rest) = split_word(rest);
+                let (last, name) = split_word(rest);
+                let (first, last) = (index(first)?, index(last)?);
+                if name.is_empty() {
+                    return Err(error("Expected the name of the corner".to_string()));
+                }
+                corners.push((name.to_string(), first, last));
+            }
+            "drs" => {
+                let values: Vec<_> = rest.split_whitespace().collect();
+                let [detection, first, last] = values[..] else {
+                    return Err(error(
+                        "Expected the detection LED and the first and last LED of the zone"
+                            .to_string(),
+                    ));
+                };
+                drs_zones.push((index(detection)?, index(first)?, index(last)?));
+            }
+            "pit" => {
+                let values: Vec<_> = rest.split_whitespace().collect();
+                let [entry, exit] = values[..] else {
+                    return Err(error("Expected the entry and exit LED".to_string()));
+                };
+                pit_lane = Some((index(entry)?, index(exit)?));
+            }
+            "led" => {
+                let mut values = rest.split_whitespace();
+                let (Some(x), Some(y), None) = (values.next(), values.next(), values.next()) else {
+                    return Err(error("Expected the x and y of the LED".to_string()));
+                };
+                positions.push((number(x)?, number(y)?));
+            }
+            _ => return Err(error(format!("Unknown line: {line}"))),
+        }
+    }
+
+    let end = source.lines().count();
+    if positions.is_empty() {
+        return Err((end, "No LEDs".to_string()));
+    }
+    if let Some((line, index)) = indices.iter().find(|(_, index)| *index >= positions.len()) {
+        return Err((*line, format!("LED {index} is past the last LED")));
+    }
+    if sectors.len() != 3 || sectors[0] != 0 {
+        return Err((
+            end,
+            "Expected three sectors, the first at LED 0".to_string(),
+        ));
+    }
+    if sectors.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err((
+            end,
+            "Sectors must be in track order and hold LEDs".to_string(),
+        ));
+    }
+
+    Ok(Descriptor {
+        name: name.ok_or((end, "No name".to_string()))?,
+        seed: seed.ok_or((end, "No seed".to_string()))?,
+        sectors,
+        positions,
+        start_finish,
+        corners,
+        drs_zones,
+        pit_lane,
+    })
+}
+
+/// The first word of a line and the rest of it
+fn split_word(line: &str) -> (&str, &str) {
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    (word, rest.trim())
+}
+
+/// `zandvoort` to `Zandvoort`, `spa_francorchamps` to `SpaFrancorchamps`
+fn type_name(module: &str) -> String {
+    module
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn generate(module: &str, file: &str, descriptor: &Descriptor) -> String {
+    let mut code = String::new();
+    let positions: String = descriptor
+        .positions
+        .iter()
+        .map(|(x, y)| format!("({x:?}, {y:?}), "))
+        .collect();
+    let [sector_1, sector_2, sector_3] = descriptor.sectors[..] else {
+        unreachable!("checked by parse")
+    };
+
+    writeln!(code, "/// Generated from `{file}`").unwrap();
+    writeln!(code, "pub mod {module} {{").unwrap();
+    writeln!(code, "    circuit! {{").unwrap();
+    writeln!(code, "        /// {}", descriptor.name).unwrap();
+    writeln!(code, "        {},", type_name(module)).unwrap();
+    writeln!(code, "        name: {:?},", descriptor.name).unwrap();
+    writeln!(code, "        seed: {},", descriptor.seed).unwrap();
+    writeln!(
+        code,
+        "        sectors: [{sector_1}, {sector_2}, {sector_3}],"
+    )
+    .unwrap();
+    writeln!(code, "        positions: [{positions}],").unwrap();
+    writeln!(code, "        start_finish: {},", descriptor.start_finish).unwrap();
+    writeln!(code, "        corners: [").unwrap();
+    for (name, first, last) in &descriptor.corners {
+        writeln!(code, "            ({name:?}, {first}, {last}),").unwrap();
+    }
+    writeln!(code, "        ],").unwrap();
+    writeln!(code, "        drs_zones: [").unwrap();
+    for (detection, first, last) in &descriptor.drs_zones {
+        writeln!(code, "            ({detection}, {first}, {last}),").unwrap();
+    }
+    writeln!(code, "        ],").unwrap();
+    match descriptor.pit_lane {
+        Some((entry, exit)) => writeln!(code, "        pit_lane: Some(({entry}, {exit})),"),
+        None => writeln!(code, "        pit_lane: None,"),
+    }
+    .unwrap();
+    writeln!(code, "    }}").unwrap();
+    writeln!(code, "}}").unwrap();
+    code
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={CIRCUITS_DIR}");
+
+    let mut files: Vec<_> = fs::read_dir(CIRCUITS_DIR)
+        .expect("Failed to read the circuits directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "circuit")
+        })
+        .collect();
+    files.sort();
+
+    let mut code = String::new();
+    for path in files {
+        let file = path.display().to_string();
+        println!("cargo:rerun-if-changed={file}");
+
+        let source = fs::read_to_string(&path).expect("Failed to read circuit");
+        let descriptor = match parse(&source) {
+            Ok(descriptor) => descriptor,
+            Err((line, message)) => panic!("{file}:{line}: {message}"),
+        };
+        let module = path.file_stem().unwrap().to_string_lossy();
+        code += &generate(&module, &file, &descriptor);
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("circuits.rs");
+    fs::write(out, code).expect("Failed to write the generated circuits");
+}
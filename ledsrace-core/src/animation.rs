@@ -1,42 +1,54 @@
 use embassy_time::{Duration, Instant};
 use heapless::Vec as HeaplessVec;
-use libm::sinf;
 
-use crate::{Circuit, Color, Priority};
+use crate::{
+    clock::{FrameClock, ManualClock},
+    fixed,
+    rng::Rng,
+    Circuit, Color, Priority,
+};
 
 mod advanced;
 mod basic;
 mod circuit_pulse;
+mod compositor;
 mod dutch_flag;
 mod ghost_car;
 mod growing_trail;
 mod lightning_sprint;
 mod mexican_wave;
 mod overtake;
-mod party;
+mod race_playback;
 mod raindrop;
+mod timeline;
+mod transition;
 mod unicorn_rainbow;
 
 pub use advanced::*;
 pub use basic::*;
 pub use circuit_pulse::*;
+pub use compositor::*;
 pub use dutch_flag::*;
 pub use ghost_car::*;
 pub use growing_trail::*;
 pub use lightning_sprint::*;
 pub use mexican_wave::*;
 pub use overtake::*;
-pub use party::*;
+pub use race_playback::*;
 pub use raindrop::*;
+pub use timeline::*;
+pub use transition::*;
 pub use unicorn_rainbow::*;
 
 /// Core trait for all animations
 pub trait Animation {
-    /// Render the current animation frame into the provided buffer
-    /// circuit: Circuit to render on
-    /// timestamp: Current animation time
-    /// buffer: Buffer to write LED states into
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration);
+    /// Advance the animation state
+    /// circuit: Circuit the animation runs on
+    /// dt: Time since the previous update
+    fn update<const N: usize, C: Circuit<N>>(&mut self, _circuit: &C, _dt: Duration) {}
+
+    /// Render the current animation state into the LED buffer of the circuit
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C);
 
     /// Returns true if the animation has finished
     fn is_finished(&self) -> bool;
@@ -44,104 +56,218 @@ pub trait Animation {
     /// Get the animation's base priority
     fn priority(&self) -> Priority;
 
-    fn reset(&self) {}
+    /// Restart the animation from the beginning
+    fn reset(&mut self) {}
+}
+
+/// An animation in an [`AnimationQueue`], with how it is played
+pub struct PlaylistEntry {
+    pub animation: Animations,
+    /// How long the animation plays, `None` to play until it finishes
+    pub duration: Option<Duration>,
+    /// How many times the animation plays in a row
+    pub repeat: u8,
+    /// Chance of being picked next when shuffling, relative to the other entries
+    pub weight: u8,
+}
+
+impl PlaylistEntry {
+    /// Play once until the animation finishes
+    pub const fn new(animation: Animations) -> Self {
+        Self {
+            animation,
+            duration: None,
+            repeat: 1,
+            weight: 1,
+        }
+    }
+
+    pub const fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub const fn with_repeat(mut self, repeat: u8) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub const fn with_weight(mut self, weight: u8) -> Self {
+        self.weight = weight;
+        self
+    }
 }
 
-// /// Manages multiple animations and renders them efficiently
-// pub struct AnimationManager<const N: usize> {
-//     animations: HeaplessVec<&'static dyn Animation, 8>, // Fixed max number of animations
-//     buffer: LedStateBuffer<N>,
-//     start_time: Instant,
-// }
-
-// impl<const N: usize> AnimationManager<N> {
-//     pub fn new() -> Self {
-//         Self {
-//             animations: HeaplessVec::new(),
-//             buffer: LedStateBuffer::new(),
-//             start_time: Instant::now(),
-//         }
-//     }
-
-//     pub fn add_animation(&mut self, animation: &'static dyn Animation) {
-//         self.animations.push(animation).ok(); // Ignore if full
-//     }
-
-//     /// Render current frame of all animations
-//     pub fn render(&mut self, current_time: Instant) -> &[(Color, Priority)] {
-//         let timestamp = current_time - self.start_time;
-
-//         // Clear buffer for new frame
-//         self.buffer.clear();
-
-//         // Render each animation in order (higher priority animations render last)
-//         for animation in self.animations.iter().filter(|a| !a.is_finished()) {
-//             animation.render(timestamp, &mut self.buffer);
-//         }
-
-//         self.buffer.get_colors()
-//     }
-// }
-
-/// Manages a queue of animations and cycles through them
-pub struct AnimationQueue {
-    animations: HeaplessVec<&'static Animations, 12>, // Fixed max number of animations
+/// Manages a queue of animations and cycles through them, in order or shuffled
+pub struct AnimationQueue<const CAP: usize = 12> {
+    entries: HeaplessVec<PlaylistEntry, CAP>,
     current_index: usize,
-    /// Start time of the current animation
-    start_time: Instant,
-    /// Maximum duration of an animation
+    /// Time the current animation has been running
+    elapsed: Duration,
+    /// Set to the time of every rendered frame, for the time since the previous one
+    frames: FrameClock<ManualClock>,
+    /// Duration of animations added with `add_animation`
     max_duration: Duration,
+    /// How the queue switches to the next animation
+    transition: Transition,
+    /// Animation being transitioned away from, it keeps running until the transition is done
+    outgoing: Option<usize>,
+    /// Times the current entry has played in a row
+    plays: u8,
+    /// Random order when shuffling, `None` to play the entries in order
+    shuffle: Option<Rng>,
 }
 
-impl AnimationQueue {
+impl<const CAP: usize> AnimationQueue<CAP> {
     pub fn new(max_duration: Duration) -> Self {
         Self {
-            animations: HeaplessVec::new(),
+            entries: HeaplessVec::new(),
             current_index: 0,
-            start_time: Instant::now(),
+            elapsed: Duration::from_ticks(0),
+            frames: FrameClock::new(ManualClock::new()),
             max_duration,
+            transition: Transition::Cut,
+            outgoing: None,
+            plays: 0,
+            shuffle: None,
         }
     }
 
-    pub fn add_animation(&mut self, animation: &'static Animations) {
-        self.animations.push(animation).ok(); // Ignore if full
+    /// Transition between animations, instead of cutting from one to the next
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Pick the next entry at random by weight, never the one that just played. The same seed
+    /// gives the same order.
+    pub fn with_shuffle(mut self, seed: u32) -> Self {
+        self.shuffle = Some(Rng::new(seed));
+        self
+    }
+
+    /// Add an animation that plays once, for at most the queue's maximum duration
+    pub fn add_animation(&mut self, animation: Animations) {
+        self.add_entry(PlaylistEntry::new(animation).with_duration(self.max_duration));
+    }
+
+    pub fn add_entry(&mut self, entry: PlaylistEntry) {
+        self.entries.push(entry).ok(); // Ignore if full
+    }
+
+    /// Returns true if no more entries can be added
+    pub fn is_full(&self) -> bool {
+        self.entries.is_full()
     }
 
+    /// Skip to the next entry, repeats of the current entry included
     pub fn next_animation(&mut self) {
-        if !self.animations.is_empty() {
-            self.current_index = (self.current_index + 1) % self.animations.len();
-            self.start_time = Instant::now(); // Reset time for new animation
-            self.animations.get(self.current_index).unwrap().reset();
+        if !self.entries.is_empty() {
+            let next = self.pick_next();
+
+            // An animation can't transition into itself
+            self.outgoing = (self.transition != Transition::Cut && next != self.current_index)
+                .then_some(self.current_index);
+
+            self.current_index = next;
+            self.elapsed = Duration::from_ticks(0); // Reset time for new animation
+            self.plays = 0;
+            self.entries[self.current_index].animation.reset();
+        }
+    }
+
+    fn pick_next(&mut self) -> usize {
+        let sequential = (self.current_index + 1) % self.entries.len();
+        let Some(rng) = self.shuffle.as_mut() else {
+            return sequential;
+        };
+
+        let current = self.current_index;
+        let weight = |(i, entry): (usize, &PlaylistEntry)| {
+            if i == current {
+                0
+            } else {
+                entry.weight as u32
+            }
+        };
+        let total: u32 = self.entries.iter().enumerate().map(weight).sum();
+        if total == 0 {
+            return sequential;
+        }
+
+        let mut pick = rng.range(0..total);
+        for (i, entry) in self.entries.iter().enumerate() {
+            let weight = weight((i, entry));
+            if pick < weight {
+                return i;
+            }
+            pick -= weight;
         }
+        sequential
     }
 
-    /// Render current frame of the current animation
+    /// Render current frame of the current animation, `current_time` is a reading of the clock
+    /// that drives the queue, see `clock`
     pub fn render<const N: usize, C: Circuit<N>>(
         &mut self,
         circuit: &mut C,
         current_time: Instant,
     ) {
+        self.frames.clock_mut().set(current_time);
+        let dt = self.frames.tick();
+        self.advance(circuit, dt);
+    }
+
+    /// Render the frame `dt` after the previous one, for callers that keep their own time
+    pub fn advance<const N: usize, C: Circuit<N>>(&mut self, circuit: &mut C, dt: Duration) {
         // Clear buffer for new frame
         circuit.led_buffer().clear();
 
-        if let Some(animation) = self.animations.get(self.current_index) {
-            // Auto-advance if current animation is finished
-            if animation.is_finished() || self.start_time.elapsed() > self.max_duration {
-                self.next_animation();
-                // Get the new animation after advancing
-                if let Some(new_animation) = self.animations.get(self.current_index) {
-                    new_animation.render(
-                        circuit,
-                        current_time.saturating_duration_since(self.start_time),
-                    );
-                }
+        let Some(entry) = self.entries.get_mut(self.current_index) else {
+            return;
+        };
+        entry.animation.update(circuit, dt);
+        self.elapsed += dt;
+
+        if let Some(outgoing) = self.outgoing {
+            if self.elapsed >= self.transition.duration() {
+                self.outgoing = None;
             } else {
-                animation.render(
-                    circuit,
-                    current_time.saturating_duration_since(self.start_time),
-                );
+                self.entries[outgoing].animation.update(circuit, dt);
             }
         }
+
+        // Auto-advance if current animation is finished, the next one starts from its first frame
+        let entry = &mut self.entries[self.current_index];
+        let done = entry.animation.is_finished()
+            || entry
+                .duration
+                .is_some_and(|duration| self.elapsed > duration);
+        if self.outgoing.is_none() && done {
+            self.plays = self.plays.saturating_add(1);
+            if self.plays < entry.repeat {
+                self.elapsed = Duration::from_ticks(0);
+                entry.animation.reset();
+            } else {
+                self.next_animation();
+            }
+        }
+
+        let Some(outgoing) = self.outgoing else {
+            self.entries[self.current_index].animation.render(circuit);
+            return;
+        };
+
+        // Both animations render on their own, then get mixed
+        self.entries[outgoing].animation.render(circuit);
+        let from: [Color; N] = core::array::from_fn(|i| circuit.led_buffer().get_colors()[i].0);
+        circuit.led_buffer().clear();
+        self.entries[self.current_index].animation.render(circuit);
+        let to: [Color; N] = core::array::from_fn(|i| circuit.led_buffer().get_colors()[i].0);
+
+        let progress =
+            self.elapsed.as_micros() as f32 / self.transition.duration().as_micros() as f32;
+        self.transition.render(circuit, &from, &to, progress);
     }
 }
 
@@ -149,21 +275,38 @@ impl AnimationQueue {
 pub struct WaveAnimation {
     pub speed: f32,
     pub wavelength: f32,
+    elapsed: Duration,
+}
+
+impl WaveAnimation {
+    pub const fn new(speed: f32, wavelength: f32) -> Self {
+        Self {
+            speed,
+            wavelength,
+            elapsed: Duration::from_ticks(0),
+        }
+    }
 }
 
 impl Animation for WaveAnimation {
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
-        let t = timestamp.as_micros() as f32 * 1e-6;
+    fn update<const N: usize, C: Circuit<N>>(&mut self, _circuit: &C, dt: Duration) {
+        self.elapsed += dt;
+    }
+
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        let t = self.elapsed.as_micros() as f32 * 1e-6;
+        let offset = fixed::turns(t * self.speed);
+        let step = (65536.0 / self.wavelength) as u32;
 
         // Generate wave pattern on the fly
         for i in 0..N {
-            let phase = (i as f32 / self.wavelength + t * self.speed) % 1.0;
+            let angle = ((i as u32).wrapping_mul(step) as u16).wrapping_add(offset);
             // Map sine wave (-1 to 1) to brightness (0 to 150)
-            let brightness = ((sinf(phase * core::f32::consts::PI * 2.0) + 1.0) * 75.0) as u8;
+            let brightness = fixed::scale_u8(150, fixed::wave(angle));
             circuit.led_buffer().set_led(
                 i,
                 Color(brightness, brightness / 10, brightness / 10), // Use all channels for better visibility
-                <WaveAnimation as Animation>::priority(&self),
+                <WaveAnimation as Animation>::priority(self),
             );
         }
     }
@@ -175,14 +318,24 @@ impl Animation for WaveAnimation {
     fn priority(&self) -> Priority {
         Priority::Background
     }
+
+    fn reset(&mut self) {
+        self.elapsed = Duration::from_ticks(0);
+    }
 }
 
 macro_rules! impl_animation_for_enum {
     ($enum_name:ident, $($variant:ident),+) => {
         impl Animation for $enum_name {
-            fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
+            fn update<const N: usize, C: Circuit<N>>(&mut self, circuit: &C, dt: Duration) {
+                match self {
+                    $( $enum_name::$variant(animation) => animation.update(circuit, dt), )+
+                }
+            }
+
+            fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
                 match self {
-                    $( $enum_name::$variant(animation) => animation.render(circuit, timestamp), )+
+                    $( $enum_name::$variant(animation) => animation.render(circuit), )+
                 }
             }
 
@@ -198,7 +351,7 @@ macro_rules! impl_animation_for_enum {
                 }
             }
 
-            fn reset(&self) {
+            fn reset(&mut self) {
                 match self {
                     $( $enum_name::$variant(animation) => animation.reset(), )+
                 }
@@ -213,7 +366,6 @@ pub enum Animations {
     ShowSectors(ShowSectors),
     SectorFrames(SectorFrames),
     RainDrop(RainDropRace),
-    Party(Party),
     OvertakeDuel(OvertakeDuel),
     GhostCar(GhostCar),
     LightningSprint(LightningSprint),
@@ -222,6 +374,7 @@ pub enum Animations {
     DutchFlag(DutchFlag),
     CircuitPulse(CircuitPulse),
     GrowingTrail(GrowingTrail),
+    RacePlayback(RacePlayback),
 }
 
 impl_animation_for_enum!(
@@ -231,7 +384,6 @@ impl_animation_for_enum!(
     ShowSectors,
     SectorFrames,
     RainDrop,
-    Party,
     OvertakeDuel,
     GhostCar,
     LightningSprint,
@@ -239,14 +391,218 @@ impl_animation_for_enum!(
     UnicornRainbow,
     DutchFlag,
     CircuitPulse,
-    GrowingTrail
+    GrowingTrail,
+    RacePlayback
 );
 
-// Helper function to scale a Color by the given brightness factor (0.0 to 1.0).
-pub fn scale_color(color: Color, brightness: f32) -> Color {
-    // Assuming Color is defined as Color(u8, u8, u8)
-    let r = (color.0 as f32 * brightness).min(255.0) as u8;
-    let g = (color.1 as f32 * brightness).min(255.0) as u8;
-    let b = (color.2 as f32 * brightness).min(255.0) as u8;
-    Color(r, g, b)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{color::Palette, zandvoort::Zandvoort};
+
+    const ORANGE: Color = Color(255, 20, 0);
+
+    fn lit_leds(zandvoort: &mut Zandvoort<216>) -> usize {
+        let colors = zandvoort.led_buffer().get_colors();
+        colors.iter().filter(|(c, _)| *c != Color(0, 0, 0)).count()
+    }
+
+    #[test]
+    fn queue_advances_after_max_duration() {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut queue: AnimationQueue = AnimationQueue::new(Duration::from_millis(100));
+        queue.add_animation(Animations::GhostCar(GhostCar::new(20.0, 8, ORANGE)));
+        queue.add_animation(Animations::Static(StaticColor::new(ORANGE)));
+
+        queue.render(&mut zandvoort, Instant::from_millis(0));
+        queue.render(&mut zandvoort, Instant::from_millis(60));
+        assert_ne!(lit_leds(&mut zandvoort), 216);
+
+        queue.render(&mut zandvoort, Instant::from_millis(120));
+        assert_eq!(lit_leds(&mut zandvoort), 216);
+    }
+
+    #[test]
+    fn queue_crossfades_between_animations() {
+        const BLUE: Color = Color(0, 0, 200);
+
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut queue: AnimationQueue = AnimationQueue::new(Duration::from_millis(100))
+            .with_transition(Transition::Crossfade(Duration::from_millis(100)));
+        queue.add_animation(Animations::Static(StaticColor::new(ORANGE)));
+        queue.add_animation(Animations::Static(StaticColor::new(BLUE)));
+
+        queue.render(&mut zandvoort, Instant::from_millis(0));
+        queue.next_animation();
+        queue.render(&mut zandvoort, Instant::from_millis(50));
+        let color = zandvoort.led_buffer().get_colors()[0].0;
+        assert_eq!(color, BlendMode::Replace.blend(ORANGE, BLUE, 127));
+
+        queue.render(&mut zandvoort, Instant::from_millis(100));
+        assert_eq!(zandvoort.led_buffer().get_colors()[0].0, BLUE);
+    }
+
+    #[test]
+    fn queue_repeats_and_shuffles_by_weight() {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut queue: AnimationQueue<4> =
+            AnimationQueue::new(Duration::from_millis(100)).with_shuffle(7);
+        let ms = Duration::from_millis;
+        queue.add_entry(
+            PlaylistEntry::new(Animations::Static(StaticColor::new(ORANGE)))
+                .with_duration(ms(10))
+                .with_repeat(3),
+        );
+        queue.add_entry(
+            PlaylistEntry::new(Animations::Static(StaticColor::new(ORANGE)))
+                .with_duration(ms(10))
+                .with_weight(3),
+        );
+        queue.add_entry(
+            PlaylistEntry::new(Animations::Static(StaticColor::new(ORANGE)))
+                .with_duration(ms(10))
+                .with_weight(0),
+        );
+        queue.add_entry(
+            PlaylistEntry::new(Animations::Static(StaticColor::new(ORANGE)))
+                .with_duration(ms(10)),
+        );
+
+        // The first entry plays three times before the queue moves on
+        queue.render(&mut zandvoort, Instant::from_millis(0));
+        for frame in 1..=3 {
+            queue.render(&mut zandvoort, Instant::from_millis(frame * 11));
+        }
+        assert_eq!(queue.current_index, 1);
+
+        let mut played = [0; 4];
+        let mut previous = queue.current_index;
+        for _ in 0..100 {
+            queue.next_animation();
+            assert_ne!(queue.current_index, previous);
+            previous = queue.current_index;
+            played[previous] += 1;
+        }
+        // Weight 3 against two entries of weight 1, without repeats that is 3/7 of the plays
+        assert_eq!(played[2], 0);
+        assert_eq!(played.iter().sum::<i32>(), 100);
+        assert!(played[1] > played[0] && played[1] > played[3], "{played:?}");
+        assert!((35..=51).contains(&played[1]), "{played:?}");
+    }
+
+    #[test]
+    fn party_raindrops_move() {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut party = RainDropRace::party(Duration::from_millis(0));
+
+        // The first raindrop spawns after 500 ms
+        party.update(&zandvoort, Duration::from_millis(600));
+        party.render(&mut zandvoort);
+        let first: [Color; 216] =
+            core::array::from_fn(|i| zandvoort.led_buffer().get_colors()[i].0);
+        assert_ne!(lit_leds(&mut zandvoort), 0);
+
+        zandvoort.led_buffer().clear();
+        party.update(&zandvoort, Duration::from_millis(100));
+        party.render(&mut zandvoort);
+        let second: [Color; 216] =
+            core::array::from_fn(|i| zandvoort.led_buffer().get_colors()[i].0);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn party_renders_the_same_for_a_seed() {
+        let frames = |party: &mut RainDropRace| -> [[Color; 216]; 2] {
+            let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+            core::array::from_fn(|_| {
+                zandvoort.led_buffer().clear();
+                party.update(&zandvoort, Duration::from_millis(1500));
+                party.render(&mut zandvoort);
+                core::array::from_fn(|i| zandvoort.led_buffer().get_colors()[i].0)
+            })
+        };
+
+        let mut party = RainDropRace::party(Duration::from_millis(0)).with_seed(3);
+        let first = frames(&mut party);
+        assert_eq!(
+            frames(&mut RainDropRace::party(Duration::from_millis(0)).with_seed(3)),
+            first
+        );
+        assert_ne!(
+            frames(&mut RainDropRace::party(Duration::from_millis(0)).with_seed(4)),
+            first
+        );
+
+        party.reset();
+        assert_eq!(frames(&mut party), first);
+    }
+
+    #[test]
+    fn pulse_repeats_every_interval() {
+        let frame = |pulse: &mut CircuitPulse, dt: u64| -> [Color; 216] {
+            let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+            pulse.update(&zandvoort, Duration::from_millis(dt));
+            pulse.render(&mut zandvoort);
+            core::array::from_fn(|i| zandvoort.led_buffer().get_colors()[i].0)
+        };
+        let pulse = |interval| CircuitPulse::new(100.0, 10.0, Palette::KINGSDAY, interval, false);
+
+        let mut repeating = pulse(Some(Duration::from_secs(4)));
+        let first = frame(&mut repeating, 100);
+        assert_ne!(first, [Color(0, 0, 0); 216]);
+        // The first pulse has left the circuit when the next one starts
+        assert_eq!(frame(&mut repeating, 4000), first);
+        assert!(!repeating.is_finished());
+
+        let mut single = pulse(None);
+        frame(&mut single, 100);
+        assert_eq!(frame(&mut single, 4000), [Color(0, 0, 0); 216]);
+        assert!(single.is_finished());
+    }
+
+    /// Frames after 1, 2 and 3 seconds, updating at `hz` frames per second
+    fn frames_at(mut animation: impl Animation, hz: u64) -> [[Color; 216]; 3] {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut frames = [[Color(0, 0, 0); 216]; 3];
+        let mut previous = Duration::from_ticks(0);
+        for frame in 1..=3 * hz {
+            // Timestamps of a real ticker, so the steps don't all have the same length
+            let now = Duration::from_micros(frame * 1_000_000 / hz);
+            animation.update(&zandvoort, now - previous);
+            previous = now;
+
+            if frame % hz == 0 {
+                zandvoort.led_buffer().clear();
+                animation.render(&mut zandvoort);
+                frames[(frame / hz - 1) as usize] =
+                    core::array::from_fn(|i| zandvoort.led_buffer().get_colors()[i].0);
+            }
+        }
+        frames
+    }
+
+    #[test]
+    fn movers_do_not_depend_on_frame_rate() {
+        let check = |at_30: [[Color; 216]; 3], at_60: [[Color; 216]; 3]| {
+            assert_eq!(at_30, at_60);
+            assert_ne!(at_30[0], at_30[1]);
+        };
+
+        check(
+            frames_at(GhostCar::new(20.0, 8, ORANGE), 30),
+            frames_at(GhostCar::new(20.0, 8, ORANGE), 60),
+        );
+        check(
+            frames_at(OvertakeDuel::new(216), 30),
+            frames_at(OvertakeDuel::new(216), 60),
+        );
+        check(
+            frames_at(LightningSprint::new(ORANGE).with_speed(33.3), 30),
+            frames_at(LightningSprint::new(ORANGE).with_speed(33.3), 60),
+        );
+        check(
+            frames_at(GrowingTrail::new(ORANGE, 20.0), 30),
+            frames_at(GrowingTrail::new(ORANGE, 20.0), 60),
+        );
+    }
 }
@@ -1,5 +1,3 @@
-use core::cell::Cell;
-
 use embassy_time::Duration;
 use heapless::Vec;
 
@@ -26,7 +24,7 @@ impl Animation for StaticColor {
         Priority::Background
     }
 
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, _timestamp: Duration) {
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
         for i in 0..circuit.led_count() {
             circuit.set_led(i, self.color, Priority::Background);
         }
@@ -85,7 +83,7 @@ impl ShowSectors {
 }
 
 impl Animation for ShowSectors {
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, _timestamp: Duration) {
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
         for (c, sector) in [Sector::_1, Sector::_2, Sector::_3].iter().enumerate() {
             for led in circuit.sector_indices(*sector) {
                 circuit.set_led(led, self.sectors[c], Priority::Background);
@@ -104,19 +102,18 @@ impl Animation for ShowSectors {
 
 pub struct SectorFrames {
     frames: Vec<[Color; 3], 10>,
-    current_frame: Cell<usize>,
-    last_update: Cell<Duration>,
+    current_frame: usize,
+    /// Time the current frame has been shown
+    frame_time: Duration,
     interval: Duration,
 }
 
-unsafe impl Sync for SectorFrames {}
-
 impl SectorFrames {
     pub const fn new(interval: Duration) -> Self {
         Self {
             frames: Vec::new(),
-            current_frame: Cell::new(0),
-            last_update: Cell::new(Duration::from_millis(0)),
+            current_frame: 0,
+            frame_time: Duration::from_millis(0),
             interval,
         }
     }
@@ -127,25 +124,30 @@ impl SectorFrames {
 }
 
 impl Animation for SectorFrames {
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
+    fn reset(&mut self) {
+        self.current_frame = 0;
+        self.frame_time = Duration::from_millis(0);
+    }
+
+    fn update<const N: usize, C: Circuit<N>>(&mut self, _circuit: &C, dt: Duration) {
+        self.frame_time += dt;
+
+        if self.frame_time >= self.interval && !self.frames.is_empty() {
+            self.frame_time = Duration::from_millis(0);
+            self.current_frame = (self.current_frame + 1) % self.frames.len();
+        }
+    }
+
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
         for (c, sector) in [Sector::_1, Sector::_2, Sector::_3].iter().enumerate() {
             for led in circuit.sector_indices(*sector) {
                 circuit.set_led(
                     led,
-                    self.frames[self.current_frame.get()][c],
+                    self.frames[self.current_frame][c],
                     Priority::Background,
                 );
             }
         }
-
-        let last = self.last_update.get();
-        let t_diff = timestamp - last;
-
-        if t_diff >= self.interval {
-            self.last_update.set(timestamp);
-            self.current_frame
-                .set((self.current_frame.get() + 1) % self.frames.len());
-        }
     }
 
     fn is_finished(&self) -> bool {
@@ -1,11 +1,9 @@
-use core::cell::Cell;
-
 use embassy_time::Duration;
 use libm::fabsf;
 
-use crate::{Circuit, Color, Priority};
+use crate::{color::Palette, Circuit, Priority};
 
-use super::{calculate_center, max_distance_from_center, Animation};
+use super::Animation;
 
 /// Animation that creates pulses moving outward from the center
 pub struct CircuitPulse {
@@ -13,42 +11,64 @@ pub struct CircuitPulse {
     pub speed: f32,
     /// Width of the pulse (in distance units)
     pub pulse_width: f32,
-    /// Colors of the pulse
-    pub colors: [Color; 3],
-    // pub color: Color,
+    /// Colors of the pulse, LEDs take turns
+    pub palette: Palette,
     /// Time between pulses (None for single pulse)
     pub repeat_interval: Option<Duration>,
-    /// Time when the animation started
-    start_time: Cell<Option<Duration>>,
+    /// Time the animation has been running
+    elapsed: Duration,
 
     /// Whether to randomize the position of the pulse
     randomize: bool,
     /// Whether the animation has finished
-    finished: Cell<bool>,
+    finished: bool,
 }
 
-unsafe impl Sync for CircuitPulse {}
-
 impl CircuitPulse {
     pub const fn new(
         speed: f32,
         pulse_width: f32,
-        colors: [Color; 3],
+        palette: Palette,
         repeat_interval: Option<Duration>,
         randomize: bool,
     ) -> Self {
+        if let Some(interval) = repeat_interval {
+            assert!(
+                interval.as_micros() > 0,
+                "repeat interval must be above zero"
+            );
+        }
         Self {
             speed,
             pulse_width,
-            colors,
+            palette,
             repeat_interval,
-            start_time: Cell::new(None),
+            elapsed: Duration::from_millis(0),
             randomize,
-            finished: Cell::new(false),
+            finished: false,
         }
     }
 
-    fn calculate_brightness(&self, distance: f32, pulse_distance: f32) -> u8 {
+    /// Distances traveled by the pulses still on the circuit, the newest first. A repeating pulse
+    /// starts again every interval, while the earlier ones are still moving outward.
+    fn pulse_distances(&self, max_distance: f32) -> impl Iterator<Item = f32> + '_ {
+        let elapsed = self.elapsed.as_micros();
+        let (first, interval) = match self.repeat_interval {
+            Some(interval) => (elapsed % interval.as_micros(), interval.as_micros()),
+            // Stepping past `elapsed` keeps it to one pulse
+            None => (elapsed, elapsed + 1),
+        };
+        // Past the furthest LED a pulse is no longer visible
+        let visible = max_distance + self.pulse_width;
+
+        (first..=elapsed)
+            .step_by(interval as usize)
+            .map(|age| age as f32 * 1e-6 * self.speed)
+            .take_while(move |distance| *distance <= visible)
+    }
+
+    /// Brightness from 0.0 to 1.0
+    fn calculate_brightness(&self, distance: f32, pulse_distance: f32) -> f32 {
         // Distance from the pulse center
         let delta = fabsf(distance - pulse_distance);
 
@@ -56,79 +76,56 @@ impl CircuitPulse {
         if delta <= self.pulse_width {
             // Create a smooth falloff at the edges of the pulse
             let normalized = 1.0 - (delta / self.pulse_width);
-            let brightness = normalized * normalized; // Square for smoother falloff
-            (brightness * 150.0) as u8 // Max brightness of 150
+            normalized * normalized // Square for smoother falloff
         } else {
-            0
+            0.0
         }
     }
 }
 
 impl Animation for CircuitPulse {
-    fn reset(&self) {
-        self.start_time.set(None);
-        self.finished.set(false);
+    fn reset(&mut self) {
+        self.elapsed = Duration::from_millis(0);
+        self.finished = false;
     }
 
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
-        let led_positions = if self.randomize {
-            circuit.led_positions_random()
-        } else {
-            circuit.led_positions()
-        };
+    fn update<const N: usize, C: Circuit<N>>(&mut self, circuit: &C, dt: Duration) {
+        self.elapsed += dt;
 
-        // Initialize start time if not set
-        if self.start_time.get().is_none() {
-            self.start_time.set(Some(timestamp));
+        // A single pulse finishes when it has moved beyond max distance, a repeating one never does
+        let max_distance = circuit.geometry().max_distance;
+        if self.repeat_interval.is_none()
+            && self.elapsed.as_micros() as f32 * 1e-6 * self.speed > max_distance * 2.0
+        {
+            self.finished = true;
         }
+    }
 
-        let elapsed = timestamp - self.start_time.get().unwrap();
-        let center = calculate_center(led_positions);
-        let max_distance = max_distance_from_center(led_positions);
-
-        // Calculate how far the pulse has traveled
-        let pulse_distance =
-            (elapsed.as_micros() as f32 * 1e-6 * self.speed) % (max_distance * 2.0);
-
-        // let colors = self.colors
-        // : Vec<Color, 3> = Vec::from_iter([
-        //     Color(150, 0, 100), // Purple color
-        //     Color(150, 50, 50), // Purple color
-        //     Color(150, 10, 10), // Purple color
-        // ]);
-
-        let num_colors = self.colors.len();
-
-        // For each LED, calculate its brightness based on distance from pulse
-        for (i, pos) in led_positions.iter().enumerate() {
-            let distance = pos.distance_to(&center);
-            let brightness = self.calculate_brightness(distance, pulse_distance);
-
-            let color_index = (i % num_colors) as usize;
-
-            if brightness > 0 {
-                circuit.set_led(
-                    i,
-                    Color(
-                        (self.colors[color_index].0 as u32 * brightness as u32 / 150) as u8,
-                        (self.colors[color_index].1 as u32 * brightness as u32 / 150) as u8,
-                        (self.colors[color_index].2 as u32 * brightness as u32 / 150) as u8,
-                    ),
-                    Priority::Normal,
-                );
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        let max_distance = circuit.geometry().max_distance;
+
+        // For each LED, calculate its brightness based on distance from the nearest pulse
+        for i in 0..circuit.led_count() {
+            let led = if self.randomize {
+                circuit.led_order_random()[i]
+            } else {
+                i
+            };
+            let distance = circuit.geometry().distance(led);
+            let brightness = self
+                .pulse_distances(max_distance)
+                .map(|pulse_distance| self.calculate_brightness(distance, pulse_distance))
+                .fold(0.0, f32::max);
+
+            if brightness > 0.0 {
+                let color = self.palette.color(i).scale_fine(brightness);
+                circuit.set_led_fine(i, color, Priority::Normal);
             }
         }
-
-        // Finish when pulse has moved beyond max distance
-        if elapsed.as_micros() as f32 * 1e-6 * self.speed
-            > max_distance_from_center(led_positions) * 2.0
-        {
-            self.finished.set(true);
-        }
     }
 
     fn is_finished(&self) -> bool {
-        self.finished.get()
+        self.finished
     }
 
     fn priority(&self) -> Priority {
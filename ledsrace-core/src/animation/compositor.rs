@@ -0,0 +1,226 @@
+use embassy_time::Duration;
+use heapless::Vec as HeaplessVec;
+
+use crate::{Circuit, Color, Priority};
+
+use super::{Animation, Animations};
+
+/// How a layer is combined with the layers below it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlendMode {
+    /// The layer covers everything below it, LEDs it leaves off included
+    Replace,
+    /// Colors are added, brightening the layers below
+    Add,
+    /// Colors are multiplied, darkening the layers below
+    Multiply,
+    /// Inverse of multiply, brightening the layers below without clipping as hard as add
+    Screen,
+    /// The layer covers the layers below only where it has LEDs lit, LEDs it leaves off are
+    /// transparent
+    AlphaOver,
+}
+
+impl BlendMode {
+    /// Blend `src` on top of `dst`, `opacity` from 0 (invisible) to 255 (fully applied)
+    pub fn blend(self, dst: Color, src: Color, opacity: u8) -> Color {
+        let channel = |d: u8, s: u8| -> u8 {
+            let blended = match self {
+                BlendMode::Replace | BlendMode::AlphaOver => s,
+                BlendMode::Add => d.saturating_add(s),
+                BlendMode::Multiply => ((d as u16 * s as u16) / 255) as u8,
+                BlendMode::Screen => 255 - (((255 - d) as u16 * (255 - s) as u16) / 255) as u8,
+            };
+            lerp(d, blended, opacity)
+        };
+
+        if self == BlendMode::AlphaOver && src == Color(0, 0, 0) {
+            return dst;
+        }
+
+        Color(
+            channel(dst.0, src.0),
+            channel(dst.1, src.1),
+            channel(dst.2, src.2),
+        )
+    }
+}
+
+/// Linear interpolation from `a` to `b`, `t` from 0 (a) to 255 (b)
+fn lerp(a: u8, b: u8, t: u8) -> u8 {
+    let a = a as i32;
+    let b = b as i32;
+    (a + ((b - a) * t as i32) / 255) as u8
+}
+
+/// An animation in a [`Compositor`], with how it is blended with the layers below
+pub struct Layer {
+    pub animation: Animations,
+    pub blend: BlendMode,
+    /// 0 (invisible) to 255 (fully applied)
+    pub opacity: u8,
+}
+
+impl Layer {
+    /// Fully opaque layer that covers the layers below where it has LEDs lit
+    pub const fn new(animation: Animations) -> Self {
+        Self {
+            animation,
+            blend: BlendMode::AlphaOver,
+            opacity: 255,
+        }
+    }
+
+    pub const fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub const fn with_opacity(mut self, opacity: u8) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
+
+/// Runs several animations at once as layers, blended from the bottom (first added) to the top.
+///
+/// Each layer renders on its own, so the priority of the LEDs it sets only matters within that
+/// layer. The composition is drawn over what is already in the LED buffer.
+pub struct Compositor<const L: usize> {
+    layers: HeaplessVec<Layer, L>,
+}
+
+impl<const L: usize> Compositor<L> {
+    pub const fn new() -> Self {
+        Self {
+            layers: HeaplessVec::new(),
+        }
+    }
+
+    /// Add a layer on top of the existing ones
+    pub fn add_layer(&mut self, layer: Layer) {
+        self.layers.push(layer).ok(); // Ignore if full
+    }
+
+    /// Layer by index, to change its opacity or blend mode while running
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut Layer> {
+        self.layers.get_mut(index)
+    }
+}
+
+impl<const L: usize> Default for Compositor<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Renders every layer on its own and blends it over the frame in the LED buffer, from the first
+/// layer up. Layers come with the opacity and brightness to draw them with.
+pub(crate) fn compose<'a, const N: usize, C: Circuit<N>>(
+    circuit: &mut C,
+    layers: impl IntoIterator<Item = (&'a Layer, u8, f32)>,
+) {
+    let mut composed: [Color; N] = core::array::from_fn(|i| circuit.led_buffer().get_colors()[i].0);
+
+    for (layer, opacity, brightness) in layers {
+        if opacity == 0 {
+            continue;
+        }
+
+        circuit.led_buffer().clear();
+        layer.animation.render(circuit);
+
+        for (dst, (src, _)) in composed.iter_mut().zip(circuit.led_buffer().get_colors()) {
+            *dst = layer.blend.blend(*dst, src.scale(brightness), opacity);
+        }
+    }
+
+    let buffer = circuit.led_buffer();
+    for (i, color) in composed.into_iter().enumerate() {
+        buffer.set_color(i, color, Priority::Normal);
+    }
+}
+
+impl<const L: usize> Animation for Compositor<L> {
+    fn update<const N: usize, C: Circuit<N>>(&mut self, circuit: &C, dt: Duration) {
+        for layer in self.layers.iter_mut() {
+            layer.animation.update(circuit, dt);
+        }
+    }
+
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        compose(
+            circuit,
+            self.layers.iter().map(|layer| (layer, layer.opacity, 1.0)),
+        );
+    }
+
+    /// The composition ends as soon as one of its layers has finished
+    fn is_finished(&self) -> bool {
+        self.layers
+            .iter()
+            .any(|layer| layer.animation.is_finished())
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Normal
+    }
+
+    fn reset(&mut self) {
+        for layer in self.layers.iter_mut() {
+            layer.animation.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{animation::StaticColor, zandvoort::Zandvoort};
+
+    const OFF: Color = Color(0, 0, 0);
+    const GREY: Color = Color(100, 100, 100);
+    const RED: Color = Color(200, 0, 0);
+
+    #[test]
+    fn blend_modes() {
+        assert_eq!(BlendMode::Replace.blend(GREY, OFF, 255), OFF);
+        assert_eq!(BlendMode::Replace.blend(GREY, RED, 0), GREY);
+        assert_eq!(BlendMode::Add.blend(GREY, RED, 255), Color(255, 100, 100));
+        assert_eq!(BlendMode::Multiply.blend(GREY, RED, 255), Color(78, 0, 0));
+        assert_eq!(
+            BlendMode::Screen.blend(GREY, RED, 255),
+            Color(222, 100, 100)
+        );
+        assert_eq!(BlendMode::AlphaOver.blend(GREY, OFF, 255), GREY);
+        assert_eq!(
+            BlendMode::AlphaOver.blend(GREY, RED, 128),
+            Color(150, 50, 50)
+        );
+    }
+
+    #[test]
+    fn layers_blend_bottom_to_top() {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut compositor: Compositor<3> = Compositor::new();
+        compositor.add_layer(
+            Layer::new(Animations::Static(StaticColor::new(GREY))).with_blend(BlendMode::Replace),
+        );
+        compositor.add_layer(
+            Layer::new(Animations::Static(StaticColor::new(RED)))
+                .with_blend(BlendMode::Add)
+                .with_opacity(0),
+        );
+        compositor.add_layer(
+            Layer::new(Animations::Static(StaticColor::new(RED))).with_blend(BlendMode::Multiply),
+        );
+
+        compositor.render(&mut zandvoort);
+        assert_eq!(zandvoort.led_buffer().get_colors()[0].0, Color(78, 0, 0));
+
+        // Turning up the hidden layer brightens the result before it is multiplied
+        compositor.layer_mut(1).unwrap().opacity = 255;
+        compositor.render(&mut zandvoort);
+        assert_eq!(zandvoort.led_buffer().get_colors()[0].0, Color(200, 0, 0));
+    }
+}
@@ -1,16 +1,25 @@
-use crate::{animation::Animation, Circuit, Color, Priority};
-use embassy_time::Duration;
+use crate::{animation::Animation, color::Palette, Circuit, Priority};
 
-pub struct DutchFlag;
+/// Horizontal stripes, one per palette color from top to bottom
+pub struct DutchFlag {
+    palette: Palette,
+}
 
 impl DutchFlag {
     pub const fn new() -> Self {
-        Self
+        Self {
+            palette: Palette::DUTCH_FLAG,
+        }
+    }
+
+    pub const fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
     }
 }
 
 impl Animation for DutchFlag {
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, _timestamp: Duration) {
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
         let led_count = circuit.led_count();
         let led_positions = circuit.led_positions();
 
@@ -23,18 +32,14 @@ impl Animation for DutchFlag {
             .iter()
             .map(|p| p.y)
             .fold(f32::NEG_INFINITY, f32::max);
-        let section_height = (y_max - y_min) / 3.0;
+        let stripes = self.palette.len().max(1);
+        let section_height = (y_max - y_min) / stripes as f32;
 
         for i in 0..led_count {
-            let y = led_positions[i].y;
-            let color = if y < y_min + section_height {
-                Color(0, 0, 255) // Blue
-            } else if y < y_min + 2.0 * section_height {
-                Color(255, 255, 255) // White
-            } else {
-                Color(255, 0, 0) // Red
-            };
-            circuit.set_led(i, color, Priority::Normal);
+            // The first color is the top stripe, at the highest y
+            let from_bottom = ((led_positions[i].y - y_min) / section_height) as usize;
+            let stripe = stripes - 1 - from_bottom.min(stripes - 1);
+            circuit.set_led(i, self.palette.color(stripe), Priority::Normal);
         }
     }
 
@@ -1,74 +1,77 @@
 // ghost_car.rs
 
-use core::cell::Cell;
 use embassy_time::Duration;
 
-use crate::{animation::Animation, Circuit, Color, Priority, Sector};
+use crate::{animation::Animation, Circuit, Color, Priority};
 
 #[derive(Debug)]
 pub struct GhostCar {
+    /// Track units per second, the same speed through corners where the LEDs are closer together
     pub speed: f32,
+    /// Length of the fading trail, in LEDs at their average spacing
     pub car_length: usize,
     pub color: Color,
-    pub current_position: Cell<f32>, // Position as a float to allow sub-LED movement
+    /// Distance of the front of the car along the track
+    pub current_position: f32,
+    elapsed: Duration,
 }
 
-unsafe impl Sync for GhostCar {}
-
 impl GhostCar {
     pub const fn new(speed: f32, car_length: usize, color: Color) -> Self {
         Self {
             speed,
             car_length,
             color,
-            current_position: Cell::new(0.0),
+            current_position: 0.0,
+            elapsed: Duration::from_millis(0),
         }
     }
 }
 
 impl Animation for GhostCar {
-    fn reset(&self) {
-        self.current_position.set(0.0);
+    fn reset(&mut self) {
+        self.current_position = 0.0;
+        self.elapsed = Duration::from_millis(0);
     }
 
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
-        let led_count = circuit.led_count();
-        let t = timestamp.as_secs() as f32;
-        let position = (self.current_position.get() + self.speed * t) % led_count as f32;
-        let start_index = position as usize;
-
-        // Get current sector
-        let sector = match start_index {
-            i if i < 77 => Sector::_1,
-            i if i < 153 => Sector::_2,
-            _ => Sector::_3,
-        };
+    fn update<const N: usize, C: Circuit<N>>(&mut self, circuit: &C, dt: Duration) {
+        // From the total time rather than adding up steps, so every frame rate ends up at the
+        // same position
+        self.elapsed += dt;
+        let seconds = self.elapsed.as_micros() as f32 * 1e-6;
+        self.current_position = circuit.geometry().wrap(self.speed * seconds);
+    }
 
-        // Get LEDs in the current sector
-        let sector_leds = circuit.sectors(sector);
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        let led_count = circuit.led_count();
+        let (behind, ahead, frac) = circuit.position_at(self.current_position);
 
-        // Set the color of the LEDs in the sector
+        // Light up the sector the car is in
         let sector_color = Color(self.color.2, self.color.1, self.color.0); // Complementary color
-        for led in sector_leds {
-            if let Some(index) = circuit
-                .led_positions()
-                .iter()
-                .position(|&p| p.x == led.x && p.y == led.y)
-            {
-                circuit.set_led(index, sector_color, Priority::Background);
-            }
+        for index in circuit.sector_indices(circuit.sector_of(behind)) {
+            circuit.set_led(index, sector_color, Priority::Background);
         }
 
-        // Render the ghost car
-        for i in 0..self.car_length {
-            let led_index = (start_index + i) % led_count;
-            let brightness = (255.0 * (1.0 - (i as f32 / self.car_length as f32))) as u8;
-            let color = Color(brightness, brightness, brightness); // White color with fading
-            circuit.set_led(led_index, color, Priority::Normal);
-        }
+        // Render the ghost car, bright at the front and fading out behind
+        let geometry = circuit.geometry();
+        let trail = self.car_length as f32 * geometry.track_length / led_count as f32;
+        let white = Color(255, 255, 255);
+
+        // Fade in the LED the car is driving towards, so the front moves smoothly between LEDs
+        let background = circuit.led_buffer().get_colors()[ahead].0;
+        circuit.set_led(ahead, background.lerp(white, frac), Priority::Normal);
 
-        self.current_position
-            .set(self.current_position.get() + self.speed * t);
+        let mut led = behind;
+        for _ in 0..led_count {
+            let geometry = circuit.geometry();
+            let distance = geometry.wrap(self.current_position - geometry.track_distance(led));
+            if distance >= trail {
+                break;
+            }
+            let color = white.scale(1.0 - distance / trail);
+            circuit.set_led(led, color, Priority::Normal);
+            led = (led + led_count - 1) % led_count;
+        }
     }
 
     fn is_finished(&self) -> bool {
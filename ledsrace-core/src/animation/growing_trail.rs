@@ -1,63 +1,78 @@
 use crate::{animation::Animation, Circuit, Color, Priority};
-use core::cell::Cell;
 use embassy_time::Duration;
 
 pub struct GrowingTrail {
-    current_pos: Cell<usize>,
-    trail_length: Cell<usize>,
+    current_pos: usize,
+    trail_length: usize,
     base_color: Color,
+    /// LEDs per second the head moves
+    speed: f32,
+    /// LEDs per second the trail grows
     grow_speed: f32,
-    finished: Cell<bool>,
+    /// Time the animation has been running
+    elapsed: Duration,
+    finished: bool,
 }
 
 impl GrowingTrail {
     pub const fn new(base_color: Color, grow_speed: f32) -> Self {
         Self {
-            current_pos: Cell::new(0),
-            trail_length: Cell::new(1),
+            current_pos: 0,
+            trail_length: 1,
             base_color,
+            speed: 150.0,
             grow_speed,
-            finished: Cell::new(false),
+            elapsed: Duration::from_millis(0),
+            finished: false,
         }
     }
-}
 
-unsafe impl Sync for GrowingTrail {}
+    /// Speed of the head in LEDs per second
+    pub const fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+}
 
 impl Animation for GrowingTrail {
-    fn reset(&self) {
-        self.current_pos.set(0);
-        self.trail_length.set(1);
-        self.finished.set(false);
+    fn reset(&mut self) {
+        self.current_pos = 0;
+        self.trail_length = 1;
+        self.elapsed = Duration::from_millis(0);
+        self.finished = false;
     }
 
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
+    fn update<const N: usize, C: Circuit<N>>(&mut self, circuit: &C, dt: Duration) {
         let led_count = circuit.led_count();
-        let pos = self.current_pos.get();
-        let next = (pos + 3) % led_count;
-        self.current_pos.set(next);
+        self.elapsed += dt;
+        let seconds = self.elapsed.as_micros() as f32 * 1e-6;
+        self.current_pos = (seconds * self.speed) as usize % led_count;
 
         // Increase trail length over time, slower than the movement of the first LED
-        if self.trail_length.get() < led_count {
-            let grow_increment = (self.grow_speed * timestamp.as_millis() as f32 / 1000.0) as usize;
-            self.trail_length.set(1 + grow_increment);
+        if self.trail_length < led_count {
+            let grow_increment = (self.grow_speed * seconds) as usize;
+            self.trail_length = 1 + grow_increment;
         }
 
-        if self.trail_length.get() >= led_count {
-            self.finished.set(true);
+        if self.trail_length >= led_count {
+            self.finished = true;
         }
+    }
+
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        let led_count = circuit.led_count();
 
         // Render the growing trail
-        for i in 0..self.trail_length.get() {
-            let trail_pos = (next + led_count - i) % led_count;
-            let intensity = 1.0 - (i as f32 / self.trail_length.get() as f32);
-            let scaled = crate::animation::scale_color(self.base_color, intensity);
-            circuit.set_led(trail_pos, scaled, Priority::Normal);
+        for i in 0..self.trail_length.min(led_count) {
+            let trail_pos = (self.current_pos + led_count - i) % led_count;
+            let intensity = 1.0 - (i as f32 / self.trail_length as f32);
+            let scaled = self.base_color.scale_fine(intensity);
+            circuit.set_led_fine(trail_pos, scaled, Priority::Normal);
         }
     }
 
     fn is_finished(&self) -> bool {
-        self.finished.get()
+        self.finished
     }
 
     fn priority(&self) -> Priority {
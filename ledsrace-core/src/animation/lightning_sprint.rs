@@ -4,88 +4,75 @@
 use crate::animation::Animation;
 use crate::Color;
 use crate::Priority;
-use core::cell::Cell;
 use embassy_time::Duration;
-use once_cell::sync::Lazy;
+use libm::floorf;
 
-const NUM_LEDS: usize = 216;
 const MAX_TRAIL: usize = 10;
 
-// Precomputed data for the animation; for example, mapping LED indices if needed.
-// For now, we just store a sequential mapping as a demonstration. In a real system you could
-// compute per-sector LED indices using Circuit trait methods.
-
-pub struct PrecomputedData {
-    pub led_indices: [usize; NUM_LEDS],
-}
-
-static PRECOMPUTED_DATA: Lazy<PrecomputedData> = Lazy::new(|| PrecomputedData {
-    led_indices: {
-        let mut arr = [0; NUM_LEDS];
-        for i in 0..NUM_LEDS {
-            arr[i] = i;
-        }
-        arr
-    },
-});
+/// Brightness of the trail, every LED further behind the head at 0.8 of the one before it
+const DECAY: [f32; MAX_TRAIL + 2] = {
+    let mut decay = [1.0; MAX_TRAIL + 2];
+    let mut i = 1;
+    while i < decay.len() {
+        decay[i] = decay[i - 1] * 0.8;
+        i += 1;
+    }
+    decay
+};
 
-// LightningSprint stores the current head position of the bolt and a fixed-length trail
-// showing the previous positions. Interior mutability via core::cell::Cell is used to update state.
+// LightningSprint works out the head of the bolt from the time it has been running, the trail
+// fades out behind it.
 
 pub struct LightningSprint {
-    current_pos: Cell<usize>,
-    // Each entry holds an Option: Some(led_index) if a trail exists at that position
-    trail: [Cell<Option<usize>>; MAX_TRAIL],
+    /// LEDs per second
+    speed: f32,
+    elapsed: Duration,
     base_color: Color,
 }
 
-unsafe impl Sync for LightningSprint {}
-
 impl LightningSprint {
     pub const fn new(base_color: Color) -> Self {
         Self {
-            current_pos: Cell::new(0),
-            trail: [const { Cell::new(None) }; MAX_TRAIL],
+            speed: 50.0,
+            elapsed: Duration::from_millis(0),
             base_color,
         }
     }
+
+    /// Speed in LEDs per second
+    pub const fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
 }
 
 impl Animation for LightningSprint {
-    fn reset(&self) {
-        self.current_pos.set(0);
+    fn reset(&mut self) {
+        self.elapsed = Duration::from_millis(0);
     }
 
-    // The render method will be called on each frame. It moves the lightning bolt along the LED strip.
-    // The current head becomes the brightest LED while previous positions form a fading trail.
-    fn render<const N: usize, C: crate::Circuit<N>>(&self, circuit: &mut C, _timestamp: Duration) {
-        // Advance the bolt along the LED indices
-        let pos = self.current_pos.get();
-        let next = (pos + 1) % NUM_LEDS;
-        self.current_pos.set(next);
-
-        // Update trail: shift existing trail entries one step back and insert current position at the front
-        for i in (1..MAX_TRAIL).rev() {
-            self.trail[i].set(self.trail[i - 1].get());
-        }
-        self.trail[0].set(Some(pos));
+    fn update<const N: usize, C: crate::Circuit<N>>(&mut self, _circuit: &C, dt: Duration) {
+        self.elapsed += dt;
+    }
 
-        // Render the animation on the circuit: for each LED, set intensity based on whether it's the head or part of the trail
-        for i in 0..NUM_LEDS {
-            let intensity = if i == next {
-                1.0
+    // The LED at the head is the brightest while the LEDs behind it form a fading trail.
+    fn render<const N: usize, C: crate::Circuit<N>>(&self, circuit: &mut C) {
+        let led_count = circuit.led_count();
+        let head = (self.elapsed.as_micros() as f32 * 1e-6 * self.speed) % led_count as f32;
+        let head_led = floorf(head) as usize % led_count;
+        let frac = head - floorf(head);
+
+        for i in 0..led_count {
+            // LEDs behind the head, the LED ahead of it fades in as the bolt gets closer
+            let behind = (head_led + led_count - i) % led_count;
+            let intensity = if behind <= MAX_TRAIL {
+                DECAY[behind] + (DECAY[behind + 1] - DECAY[behind]) * frac
+            } else if behind == led_count - 1 {
+                frac
             } else {
-                let mut val = 0.0;
-                for (j, cell) in self.trail.iter().enumerate() {
-                    if let Some(trail_led) = cell.get() {
-                        if trail_led == i {
-                            val += exp_decay(0.8, (j as i32) + 1);
-                        }
-                    }
-                }
-                val
+                0.0
             };
-            let scaled = crate::animation::scale_color(self.base_color, intensity);
+            let scaled = self.base_color.scale(intensity);
             circuit.set_led(i, scaled, Priority::Normal);
         }
     }
@@ -98,12 +85,3 @@ impl Animation for LightningSprint {
         Priority::Normal
     }
 }
-
-// Helper function to compute exponentiation by an integer power
-fn exp_decay(base: f32, exp: i32) -> f32 {
-    let mut result = 1.0;
-    for _ in 0..exp {
-        result *= base;
-    }
-    result
-}
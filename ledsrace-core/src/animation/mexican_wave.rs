@@ -1,63 +1,84 @@
-use core::cell::Cell;
+use core::f32::consts::TAU;
+
 use embassy_time::Duration;
-use libm::{cosf, sinf};
 
-use crate::{animation::Animation, Circuit, Color, Priority};
+use crate::{
+    animation::Animation,
+    fixed::{self, scale_u8, sin, turns, wave},
+    rng::Rng,
+    Circuit, Color, Priority,
+};
+
+/// Seed of waves that don't set their own
+const DEFAULT_SEED: u32 = 1;
 
 #[derive(Debug)]
 pub struct MexicanWave {
-    pub speed: f32,                   // Wave movement speed
-    pub wave_width: f32,              // Width of the wave in LEDs
-    pub base_color: Color,            // Base color of the wave
-    pub time: Cell<f32>,              // Track animation time
-    pub sparkle_positions: Cell<u32>, // Bit field for sparkle positions
+    pub speed: f32,             // Wave movement speed
+    pub wave_width: f32,        // Width of the wave in LEDs
+    pub base_color: Color,      // Base color of the wave
+    pub time: f32,              // Track animation time
+    pub sparkle_positions: u32, // Bit field for sparkle positions
+    seed: u32,
+    rng: Rng,
 }
 
-unsafe impl Sync for MexicanWave {}
-
 impl MexicanWave {
     pub const fn new(speed: f32, wave_width: f32, base_color: Color) -> Self {
         Self {
             speed,
             wave_width,
             base_color,
-            time: Cell::new(0.0),
-            sparkle_positions: Cell::new(0),
+            time: 0.0,
+            sparkle_positions: 0,
+            seed: DEFAULT_SEED,
+            rng: Rng::new(DEFAULT_SEED),
         }
     }
+
+    /// Seed of the sparkles, the same seed renders the same frames
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+        self
+    }
 }
 
 impl Animation for MexicanWave {
-    fn reset(&self) {
-        self.time.set(0.0);
-        self.sparkle_positions.set(0);
+    fn reset(&mut self) {
+        self.time = 0.0;
+        self.sparkle_positions = 0;
+        self.rng = Rng::new(self.seed);
     }
 
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
-        let t = timestamp.as_micros() as f32 * 1e-6;
-        self.time.set(t);
+    fn update<const N: usize, C: Circuit<N>>(&mut self, _circuit: &C, dt: Duration) {
+        self.time += dt.as_micros() as f32 * 1e-6;
+        let t = self.time;
 
-        let led_count = circuit.led_count();
-
-        // Update sparkle positions first
-        let new_sparkles = (sinf(t * 3.0)).abs() > 0.7; // More frequent sparkles
+        // Update sparkle positions
+        let new_sparkles = sin(turns(t * 3.0 / TAU)).abs() > fixed::from_f32(0.7); // More frequent sparkles
         if new_sparkles {
-            let mut current = self.sparkle_positions.get();
-            // Create more sparkle bits and make them move faster
-            current = (current.rotate_left(2) | (cosf(t * 17.0) as u32)) & 0x55555555; // Alternate bits
-            self.sparkle_positions.set(current);
+            // Create more sparkle bits and make them move faster, on alternate bits
+            self.sparkle_positions =
+                (self.sparkle_positions.rotate_left(2) | self.rng.chance(0.5) as u32) & 0x55555555;
         }
+    }
+
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        let t = self.time;
+        let led_count = circuit.led_count();
 
         // Create the wave pattern with sparkles
-        let sparkle_pattern = self.sparkle_positions.get();
+        let sparkle_pattern = self.sparkle_positions;
+        let offset = turns(t * self.speed);
         for i in 0..led_count {
-            let phase = ((i as f32 / led_count as f32) + t * self.speed) % 1.0;
-            let wave = (sinf(phase * core::f32::consts::PI * 2.0) + 1.0) / 2.0;
+            let angle = ((i * 65536 / led_count) as u16).wrapping_add(offset);
+            let wave = wave(angle);
 
             let base_color = Color(
-                ((self.base_color.0 as f32 * wave) as u8).max(20),
-                ((self.base_color.1 as f32 * wave) as u8).max(20),
-                ((self.base_color.2 as f32 * wave) as u8).max(20),
+                scale_u8(self.base_color.0, wave).max(20),
+                scale_u8(self.base_color.1, wave).max(20),
+                scale_u8(self.base_color.2, wave).max(20),
             );
 
             if (sparkle_pattern & (1 << (i % 32))) != 0 {
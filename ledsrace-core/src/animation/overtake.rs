@@ -1,106 +1,196 @@
-use crate::{animation::Animation, Circuit, Color, Priority};
-use core::cell::Cell;
+use crate::{animation::Animation, color::Palette, Circuit, Color, Priority};
 use embassy_time::Duration;
 
 /// Animation: Two comets chasing and overtaking each other around the circuit
 pub struct OvertakeDuel {
-    pub a_pos: Cell<usize>,    // Car A position
-    pub b_pos: Cell<usize>,    // Car B position
-    pub a_fast: Cell<bool>,    // Is A in catch-up mode?
-    pub timer: Cell<u32>,      // Animation time in frames
-    pub flash_timer: Cell<u8>, // Flash effect timer
+    /// Speed of the car that is chasing, in LEDs per second at their average spacing
+    pub fast_speed: f32,
+    /// Speed of the car that is being chased, in LEDs per second at their average spacing
+    pub slow_speed: f32,
+    pub a_fast: bool, // Is A in catch-up mode?
+    /// How far B starts ahead of A, in LEDs
+    start_gap: f32,
+    elapsed: Duration,
+    /// When the current chase started, and where both cars were at that moment
+    chase_start: Duration,
+    a_start: f32,
+    b_start: f32,
+    /// When A last caught up with B
+    overtake_at: Option<Duration>,
+    /// Car A, car B, the overtake flash and the wake behind the cars
+    palette: Palette,
 }
 
-unsafe impl Sync for OvertakeDuel {}
-
 const COMET_LEN: usize = 7;
-const FLASH_LEN: u8 = 8;
-// const A_COLOR: Color = Color(200, 30, 30); // Red
-// const B_COLOR: Color = Color(30, 30, 200); // Blue
-// const FLASH_COLOR: Color = Color(255, 255, 80); // Yellowish
-// const WAKE_COLOR: Color = Color(20, 10, 20); // Subtle background
-const A_COLOR: Color = Color(255, 0, 0); // Red
-const B_COLOR: Color = Color(0, 00, 250); // Blue
-const FLASH_COLOR: Color = Color(255, 255, 200); // Yellowish
-const WAKE_COLOR: Color = Color(20, 2, 00); // Subtle background
+/// How close A gets to B before they swap roles, in LEDs
+const CATCH_UP_GAP: f32 = 3.0;
+const FLASH_TIME: Duration = Duration::from_millis(160);
+/// Shortest chase, on a circuit too short for the gaps the cars would otherwise swap roles over
+/// and over without time passing
+const MIN_CHASE: Duration = Duration::from_millis(100);
 
 impl OvertakeDuel {
     pub const fn new(led_count: usize) -> Self {
         Self {
-            a_pos: Cell::new(0),
-            b_pos: Cell::new(led_count / 3), // Start B 1/3 lap ahead
-            a_fast: Cell::new(true),
-            timer: Cell::new(0),
-            flash_timer: Cell::new(0),
+            fast_speed: 100.0,
+            slow_speed: 50.0,
+            a_fast: true,
+            start_gap: (led_count / 3) as f32, // Start B 1/3 lap ahead
+            elapsed: Duration::from_millis(0),
+            chase_start: Duration::from_millis(0),
+            a_start: 0.0,
+            b_start: (led_count / 3) as f32,
+            overtake_at: None,
+            palette: Palette::OVERTAKE,
+        }
+    }
+
+    pub const fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Speeds of the chasing and the chased car, in LEDs per second. The chasing car has to be
+    /// faster to catch up.
+    pub const fn with_speeds(mut self, fast_speed: f32, slow_speed: f32) -> Self {
+        assert!(
+            slow_speed > 0.0 && fast_speed > slow_speed,
+            "fast speed must be above slow speed"
+        );
+        self.fast_speed = fast_speed;
+        self.slow_speed = slow_speed;
+        self
+    }
+
+    /// Positions of A and B at `time`, during the current chase
+    fn positions_at(&self, time: Duration, led_count: usize) -> (f32, f32) {
+        let (a_speed, b_speed) = if self.a_fast {
+            (self.fast_speed, self.slow_speed)
+        } else {
+            (self.slow_speed, self.fast_speed)
+        };
+        let seconds = (time - self.chase_start).as_micros() as f32 * 1e-6;
+        let wrap = |position: f32| position % led_count as f32;
+        (
+            wrap(self.a_start + a_speed * seconds),
+            wrap(self.b_start + b_speed * seconds),
+        )
+    }
+
+    /// How long the current chase lasts: until A catches up with B, or until B is half a lap
+    /// ahead again
+    fn chase_duration(&self, led_count: usize) -> Duration {
+        let gap = Self::distance(self.a_start, self.b_start, led_count);
+        let target = if self.a_fast {
+            CATCH_UP_GAP
+        } else {
+            led_count as f32 / 2.0
+        };
+        let closing_speed = self.fast_speed - self.slow_speed;
+        let seconds = (gap - target).abs() / closing_speed;
+        // Cars that close in very slowly wait an hour at most
+        Duration::from_micros((seconds * 1e6).min(3.6e9) as u64).max(MIN_CHASE)
+    }
+
+    /// Swap the roles for every chase that ended by `elapsed`. Work out when the roles swap from
+    /// the start of the chase instead of checking every frame, so every frame rate sees the same
+    /// overtakes.
+    fn swap_roles(&mut self, led_count: usize) {
+        loop {
+            let swap_at = self.chase_start + self.chase_duration(led_count);
+            if self.elapsed < swap_at {
+                break;
+            }
+
+            let (a, b) = self.positions_at(swap_at, led_count);
+            if self.a_fast {
+                self.overtake_at = Some(swap_at);
+            }
+            self.a_fast = !self.a_fast; // B now chases A, or A resumes the chase
+            self.chase_start = swap_at;
+            self.a_start = a;
+            self.b_start = b;
         }
     }
 
-    fn step_positions(&self, led_count: usize) {
-        let (a_speed, b_speed) = if self.a_fast.get() { (2, 1) } else { (1, 2) };
-        self.a_pos.set((self.a_pos.get() + a_speed) % led_count);
-        self.b_pos.set((self.b_pos.get() + b_speed) % led_count);
+    /// Distance along the track of a position in LEDs at their average spacing
+    fn track_distance<const N: usize, C: Circuit<N>>(circuit: &C, position: f32) -> f32 {
+        position * circuit.geometry().track_length / circuit.led_count() as f32
     }
 
-    fn distance(a: usize, b: usize, led_count: usize) -> usize {
+    /// Draw a car with its front at `position`, fading the LEDs at both ends in and out so the
+    /// car moves smoothly between LEDs
+    fn draw_car<const N: usize, C: Circuit<N>>(circuit: &mut C, position: f32, color: Color) {
+        let led_count = circuit.led_count();
+        let (behind, ahead, frac) = circuit.position_at(Self::track_distance(circuit, position));
+
+        let blend = |circuit: &mut C, led: usize, amount: f32| {
+            let background = circuit.led_buffer().get_colors()[led].0;
+            circuit.set_led(led, background.lerp(color, amount), Priority::Normal);
+        };
+        blend(circuit, ahead, frac);
+        for i in 0..COMET_LEN - 1 {
+            circuit.set_led(
+                (behind + led_count - i) % led_count,
+                color,
+                Priority::Normal,
+            );
+        }
+        blend(
+            circuit,
+            (behind + led_count - (COMET_LEN - 1)) % led_count,
+            1.0 - frac,
+        );
+    }
+
+    /// How far B is ahead of A
+    fn distance(a: f32, b: f32, led_count: usize) -> f32 {
         if b >= a {
             b - a
         } else {
-            led_count - a + b
+            led_count as f32 - a + b
         }
     }
 }
 
 impl Animation for OvertakeDuel {
-    fn reset(&self) {
-        self.timer.set(0);
-        self.flash_timer.set(0);
+    fn reset(&mut self) {
+        self.a_fast = true;
+        self.elapsed = Duration::from_millis(0);
+        self.chase_start = Duration::from_millis(0);
+        self.a_start = 0.0;
+        self.b_start = self.start_gap;
+        self.overtake_at = None;
     }
 
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, _timestamp: Duration) {
-        update_overtake_duel(self, N);
+    fn update<const N: usize, C: Circuit<N>>(&mut self, circuit: &C, dt: Duration) {
+        self.elapsed += dt;
+        self.swap_roles(circuit.led_count());
+    }
+
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        let led_count = circuit.led_count();
+        let (a, b) = self.positions_at(self.elapsed, led_count);
 
-        let led_count = N;
         // Clear to wake color
         for i in 0..led_count {
             circuit
                 .led_buffer()
-                .set_led(i, WAKE_COLOR, Priority::Background);
-        }
-        // Draw comets
-        for i in 0..COMET_LEN {
-            // Prevent overflow in fade calculation
-            let fade = if COMET_LEN > 0 {
-                ((COMET_LEN - i) as u8)
-                    .saturating_mul(200)
-                    .checked_div(COMET_LEN as u8)
-                    .unwrap_or(30)
-                    .max(30)
-            } else {
-                30
-            };
-            let a_idx = (self.a_pos.get() + led_count - i) % led_count;
-            let b_idx = (self.b_pos.get() + led_count - i) % led_count;
-            // circuit
-            //     .led_buffer()
-            //     .set_led(a_idx, Color(fade,0,0), Priority::Normal);
-            // circuit
-            //     .led_buffer()
-            //     .set_led(b_idx, Color(0,0,fade), Priority::Normal);
-            circuit
-                .led_buffer()
-                .set_led(a_idx, A_COLOR, Priority::Normal);
-            circuit
-                .led_buffer()
-                .set_led(b_idx, B_COLOR, Priority::Normal);
+                .set_led(i, self.palette.color(3), Priority::Background);
         }
+        Self::draw_car(circuit, a, self.palette.color(0));
+        Self::draw_car(circuit, b, self.palette.color(1));
+
         // Flash on overtake
-        if self.flash_timer.get() > 0 {
-            let idx = self.a_pos.get();
-            for d in 0..4 {
-                let fidx = (idx + d) % led_count;
-                circuit
-                    .led_buffer()
-                    .set_led(fidx, FLASH_COLOR, Priority::Normal);
+        if let Some(overtake_at) = self.overtake_at {
+            if self.elapsed - overtake_at < FLASH_TIME {
+                let (a_pos, _, _) = circuit.position_at(Self::track_distance(circuit, a));
+                for d in 0..4 {
+                    let fidx = (a_pos + d) % led_count;
+                    circuit
+                        .led_buffer()
+                        .set_led(fidx, self.palette.color(2), Priority::Normal);
+                }
             }
         }
     }
@@ -113,19 +203,25 @@ impl Animation for OvertakeDuel {
     }
 }
 
-/// State update function for OvertakeDuel (call in main loop or timer)
-pub fn update_overtake_duel(anim: &OvertakeDuel, led_count: usize) {
-    anim.timer.set(anim.timer.get() + 1);
-    if anim.flash_timer.get() > 0 {
-        anim.flash_timer.set(anim.flash_timer.get() - 1);
-    }
-    // Check for overtake
-    let dist = OvertakeDuel::distance(anim.a_pos.get(), anim.b_pos.get(), led_count);
-    if dist < 3 && anim.a_fast.get() {
-        anim.flash_timer.set(FLASH_LEN);
-        anim.a_fast.set(false); // B now chases A
-    } else if dist > led_count / 2 && !anim.a_fast.get() {
-        anim.a_fast.set(true); // A resumes chase
-    }
-    anim.step_positions(led_count);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_circuit_keeps_swapping() {
+        // Half a lap is the catch up gap, so a chase to half a lap ends where it starts
+        const LED_COUNT: usize = 6;
+        let mut duel = OvertakeDuel::new(LED_COUNT);
+        duel.elapsed = Duration::from_secs(1);
+        duel.swap_roles(LED_COUNT);
+
+        assert!(duel.overtake_at.is_some());
+        assert!(duel.elapsed - duel.chase_start < MIN_CHASE);
+    }
+
+    #[test]
+    #[should_panic(expected = "fast speed must be above slow speed")]
+    fn equal_speeds_are_rejected() {
+        OvertakeDuel::new(216).with_speeds(50.0, 50.0);
+    }
 }
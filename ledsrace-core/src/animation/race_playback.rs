@@ -0,0 +1,138 @@
+use embassy_time::Duration;
+
+use crate::{animation::Animation, data_frame::UpdateFrame, Circuit, Color, Priority};
+
+/// Plays back recorded car positions, one [`UpdateFrame`] per frame interval
+pub struct RacePlayback {
+    /// Serialized frames
+    data: &'static [u8],
+    frame_interval: Duration,
+    /// Color of a car by driver number, `None` for cars that should not be shown
+    driver_color: fn(u8) -> Option<Color>,
+    frame: UpdateFrame,
+    /// Offset in `data` of the next frame
+    next_offset: usize,
+    /// Time the current frame has been shown
+    frame_time: Duration,
+    finished: bool,
+}
+
+impl RacePlayback {
+    pub fn new(
+        data: &'static [u8],
+        frame_interval: Duration,
+        driver_color: fn(u8) -> Option<Color>,
+    ) -> Self {
+        let mut playback = Self {
+            data,
+            frame_interval,
+            driver_color,
+            frame: UpdateFrame::default(),
+            next_offset: 0,
+            frame_time: Duration::from_millis(0),
+            finished: false,
+        };
+        playback.next_frame();
+        playback
+    }
+
+    /// Load the next frame, the playback finishes at the end of the data or on a corrupt frame
+    fn next_frame(&mut self) {
+        let frame = self
+            .data
+            .get(self.next_offset..self.next_offset + UpdateFrame::SERIALIZED_SIZE)
+            .and_then(|bytes| UpdateFrame::try_from_bytes(bytes).ok());
+
+        match frame {
+            Some(frame) => {
+                self.frame = frame;
+                self.next_offset += UpdateFrame::SERIALIZED_SIZE;
+            }
+            None => self.finished = true,
+        }
+    }
+}
+
+impl Animation for RacePlayback {
+    fn reset(&mut self) {
+        self.next_offset = 0;
+        self.frame_time = Duration::from_millis(0);
+        self.finished = false;
+        self.next_frame();
+    }
+
+    fn update<const N: usize, C: Circuit<N>>(&mut self, _circuit: &C, dt: Duration) {
+        self.frame_time += dt;
+
+        while self.frame_time >= self.frame_interval && !self.finished {
+            self.frame_time -= self.frame_interval;
+            self.next_frame();
+        }
+    }
+
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        if self.finished {
+            return;
+        }
+
+        // LEDs without a car stay off
+        for driver in &self.frame.frame {
+            if let Some(color) = (self.driver_color)(driver.driver_number) {
+                circuit.set_led(driver.led_num as usize, color, Priority::Normal);
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zandvoort::Zandvoort;
+
+    const RED: Color = Color(255, 0, 0);
+
+    /// Two frames with driver 1 at LED 5 and then LED 6, the other drivers are 0
+    static DATA: [u8; 2 * UpdateFrame::SERIALIZED_SIZE] = {
+        let mut data = [0; 2 * UpdateFrame::SERIALIZED_SIZE];
+        data[0] = 1;
+        data[1] = 5;
+        data[UpdateFrame::SERIALIZED_SIZE] = 1;
+        data[UpdateFrame::SERIALIZED_SIZE + 1] = 6;
+        data
+    };
+
+    fn driver_color(driver_number: u8) -> Option<Color> {
+        (driver_number == 1).then_some(RED)
+    }
+
+    #[test]
+    fn plays_frames_until_end_of_data() {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut playback = RacePlayback::new(&DATA, Duration::from_millis(50), driver_color);
+
+        playback.render(&mut zandvoort);
+        assert_eq!(zandvoort.led_buffer().get_colors()[5].0, RED);
+
+        zandvoort.led_buffer().clear();
+        playback.update(&zandvoort, Duration::from_millis(50));
+        playback.render(&mut zandvoort);
+        let colors = zandvoort.led_buffer().get_colors();
+        assert_eq!(colors[5].0, Color(0, 0, 0));
+        assert_eq!(colors[6].0, RED);
+        assert!(!playback.is_finished());
+
+        playback.update(&zandvoort, Duration::from_millis(50));
+        assert!(playback.is_finished());
+
+        playback.reset();
+        assert!(!playback.is_finished());
+    }
+}
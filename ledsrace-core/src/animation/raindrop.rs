@@ -1,18 +1,25 @@
-use core::cell::Cell;
+use core::ops::Range;
+
 use embassy_time::Duration;
 use heapless::Vec as HeaplessVec;
 
-use crate::Circuit;
+use crate::{rng::Rng, Circuit};
 
 use super::{Animation, Color, Priority};
 
 /// Maximum number of raindrops that can be active at once
 const MAX_RAINDROPS: usize = 8;
 
+/// How long a lightning flash lasts
+const FLASH_DURATION: Duration = Duration::from_millis(100);
+
+/// Seed of animations that don't set their own
+const DEFAULT_SEED: u32 = 1;
+
 /// Represents a single raindrop moving along the circuit
 struct Raindrop {
-    /// Current position (LED index)
-    position: usize,
+    /// Current position (LED index, fractional to allow sub-LED movement)
+    position: f32,
     /// Speed in LEDs per second
     speed: f32,
     /// Length of the tail (in LEDs)
@@ -23,66 +30,96 @@ struct Raindrop {
     hue_shift_rate: f32,
 }
 
+/// How the raindrops of a `RainDropRace` look and move
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaindropStyle {
+    /// Speed in LEDs per second
+    pub speed: Range<f32>,
+    /// Length of the tail (in LEDs)
+    pub tail_length: Range<u32>,
+    /// Hue the raindrops start with (0.0 - 1.0)
+    pub hue: Range<f32>,
+    /// How fast the hue changes, an empty range keeps the start hue
+    pub hue_shift_rate: Range<f32>,
+    /// Saturation of the raindrops (0.0 - 1.0)
+    pub saturation: f32,
+}
+
+impl RaindropStyle {
+    /// Blue to teal like rain, at a visible but gentle pace
+    pub const RAIN: Self = Self {
+        speed: 3.0..6.5,
+        tail_length: 3..7,
+        hue: 0.5..0.64,
+        hue_shift_rate: 0.0..0.0,
+        saturation: 0.7,
+    };
+
+    /// Fast raindrops in every color, cycling through the hues
+    pub const PARTY: Self = Self {
+        speed: 20.0..60.0,
+        tail_length: 6..11,
+        hue: 0.0..1.0,
+        hue_shift_rate: 0.05..0.15,
+        saturation: 1.0,
+    };
+}
+
 /// Animation that simulates raindrops racing around the circuit
 pub struct RainDropRace {
+    style: RaindropStyle,
     /// Active raindrops
     raindrops: HeaplessVec<Raindrop, MAX_RAINDROPS>,
-    /// Time of last update
-    last_update: Cell<Duration>,
-    /// Time of last raindrop creation
-    last_raindrop: Cell<Duration>,
+    /// Time the animation has been running
+    elapsed: Duration,
+    /// Time since the last raindrop was created
+    since_raindrop: Duration,
     /// Time between raindrop spawns (randomized)
-    spawn_interval: Cell<Duration>,
-    /// Time of last lightning flash
-    last_flash: Cell<Duration>,
+    spawn_interval: Duration,
+    /// Time since the last lightning flash started
+    since_flash: Duration,
+    /// Time between lightning flashes (randomized)
+    flash_interval: Duration,
     /// Whether a lightning flash is currently active
-    flash_active: Cell<bool>,
+    flash_active: bool,
     /// Duration of the animation (or 0 for infinite)
     duration: Duration,
-    /// Start time
-    start_time: Cell<Duration>,
+    seed: u32,
+    rng: Rng,
 }
 
-unsafe impl Sync for RainDropRace {}
-
 impl RainDropRace {
+    /// Rain, see `RaindropStyle::RAIN`
     pub const fn new(duration: Duration) -> Self {
+        Self::with_style(RaindropStyle::RAIN, duration)
+    }
+
+    /// Colorful raindrops, see `RaindropStyle::PARTY`
+    pub const fn party(duration: Duration) -> Self {
+        Self::with_style(RaindropStyle::PARTY, duration)
+    }
+
+    pub const fn with_style(style: RaindropStyle, duration: Duration) -> Self {
         Self {
+            style,
             raindrops: HeaplessVec::new(),
-            last_update: Cell::new(Duration::from_millis(0)),
-            last_raindrop: Cell::new(Duration::from_millis(0)),
-            spawn_interval: Cell::new(Duration::from_millis(500)),
-            last_flash: Cell::new(Duration::from_millis(0)),
-            flash_active: Cell::new(false),
+            elapsed: Duration::from_millis(0),
+            since_raindrop: Duration::from_millis(0),
+            spawn_interval: Duration::from_millis(500),
+            since_flash: Duration::from_millis(0),
+            flash_interval: Duration::from_secs(5),
+            flash_active: false,
             duration,
-            start_time: Cell::new(Duration::from_millis(0)),
+            seed: DEFAULT_SEED,
+            rng: Rng::new(DEFAULT_SEED),
         }
     }
 
-    /// Convert HSV to RGB color
-    /// h: 0.0 - 1.0 (hue)
-    /// s: 0.0 - 1.0 (saturation)
-    /// v: 0.0 - 1.0 (value/brightness)
-    fn hsv_to_rgb(&self, h: f32, s: f32, v: f32) -> Color {
-        let h = h % 1.0;
-        let h6 = h * 6.0;
-        let i = h6 as u8;
-        let f = h6 - i as f32;
-
-        let p = v * (1.0 - s);
-        let q = v * (1.0 - s * f);
-        let t = v * (1.0 - s * (1.0 - f));
-
-        let (r, g, b) = match i {
-            0 => (v, t, p),
-            1 => (q, v, p),
-            2 => (p, v, t),
-            3 => (p, q, v),
-            4 => (t, p, v),
-            _ => (v, p, q),
-        };
-
-        Color((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+    /// Seed of the random raindrops and lightning, the same seed renders the same frames
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+        self
     }
 
     /// Create a new raindrop at a random position
@@ -92,25 +129,21 @@ impl RainDropRace {
             return;
         }
 
-        // Use a simple hash of the current time to generate pseudo-random values
-        let time_hash = self.last_raindrop.get().as_micros() as u32;
-        let position = (time_hash % led_count as u32) as usize;
-
-        // Vary speed between 10-30 LEDs per second
-        let speed_factor = (time_hash % 20) as f32 + 10.0;
-
-        // Vary tail length between 3-8 LEDs
-        let tail_length = ((time_hash % 6) + 3) as usize;
+        let position = self.rng.range(0..led_count as u32) as f32;
 
-        // Start with a random hue
-        let hue = (time_hash % 100) as f32 / 100.0;
-
-        // Vary hue shift rate
-        let hue_shift_rate = (time_hash % 10) as f32 / 100.0 + 0.02;
+        let style = &self.style;
+        let speed = self.rng.float_range(style.speed.clone());
+        let tail_length = self.rng.range(style.tail_length.clone()) as usize;
+        let hue = self.rng.float_range(style.hue.clone());
+        let hue_shift_rate = if style.hue_shift_rate.is_empty() {
+            style.hue_shift_rate.start
+        } else {
+            self.rng.float_range(style.hue_shift_rate.clone())
+        };
 
         let raindrop = Raindrop {
             position,
-            speed: speed_factor,
+            speed,
             tail_length,
             hue,
             hue_shift_rate,
@@ -119,86 +152,67 @@ impl RainDropRace {
         self.raindrops.push(raindrop).ok();
 
         // Set next spawn interval (500-2000ms)
-        let next_interval = 500 + (time_hash % 1500);
-        self.spawn_interval
-            .set(Duration::from_millis(next_interval as u64));
+        self.spawn_interval = Duration::from_millis(self.rng.range(500..2000) as u64);
     }
 
     /// Update raindrop positions based on elapsed time
-    fn update_raindrops(&mut self, timestamp: Duration, led_count: usize) {
-        let last_time = self.last_update.get();
-        let elapsed = timestamp - last_time;
-        let elapsed_secs = elapsed.as_micros() as f32 * 1e-6;
-
-        // Update each raindrop
-        let mut i = 0;
-        while i < self.raindrops.len() {
-            let raindrop = &mut self.raindrops[i];
+    fn update_raindrops(&mut self, dt: Duration, led_count: usize) {
+        let elapsed_secs = dt.as_micros() as f32 * 1e-6;
 
+        for raindrop in self.raindrops.iter_mut() {
             // Update position
-            let distance = (raindrop.speed * elapsed_secs) as usize;
-            raindrop.position = (raindrop.position + distance) % led_count;
+            raindrop.position =
+                (raindrop.position + raindrop.speed * elapsed_secs) % led_count as f32;
 
             // Update hue
             raindrop.hue = (raindrop.hue + raindrop.hue_shift_rate * elapsed_secs) % 1.0;
-
-            i += 1;
         }
-
-        self.last_update.set(timestamp);
     }
 
     /// Check if it's time to spawn a new raindrop
-    fn check_spawn_raindrop(&mut self, timestamp: Duration, led_count: usize) {
-        let last_spawn = self.last_raindrop.get();
-        let interval = self.spawn_interval.get();
+    fn check_spawn_raindrop(&mut self, dt: Duration, led_count: usize) {
+        self.since_raindrop += dt;
 
-        if timestamp - last_spawn >= interval {
+        if self.since_raindrop >= self.spawn_interval {
             self.spawn_raindrop(led_count);
-            self.last_raindrop.set(timestamp);
+            self.since_raindrop = Duration::from_millis(0);
         }
     }
 
-    /// Check if it's time for a lightning flash
-    fn check_lightning(&self, timestamp: Duration) -> bool {
-        let last_flash = self.last_flash.get();
+    /// Start or end a lightning flash
+    fn check_lightning(&mut self, dt: Duration) {
+        self.since_flash += dt;
 
-        // Lightning occurs roughly every 5-10 seconds
-        if self.flash_active.get() {
-            // Flash lasts for 100ms
-            if timestamp - last_flash >= Duration::from_millis(100) {
-                self.flash_active.set(false);
-            }
-            true
-        } else {
-            // Check if it's time for a new flash
-            let time_hash = timestamp.as_millis() as u32;
-            if timestamp - last_flash >= Duration::from_secs(5) && time_hash % 100 < 2 {
-                self.last_flash.set(timestamp);
-                self.flash_active.set(true);
-                true
-            } else {
-                false
+        if self.flash_active {
+            if self.since_flash >= FLASH_DURATION {
+                self.flash_active = false;
             }
+        } else if self.since_flash >= self.flash_interval {
+            self.since_flash = Duration::from_millis(0);
+            self.flash_active = true;
+
+            // Lightning occurs roughly every 5-10 seconds
+            self.flash_interval = Duration::from_millis(self.rng.range(5000..10000) as u64);
         }
     }
 
     /// Render a raindrop with its tail
     fn render_raindrop<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, raindrop: &Raindrop) {
         let led_count = circuit.led_count();
+        let position = raindrop.position as usize % led_count;
 
         // Render the head of the raindrop
-        let head_color = self.hsv_to_rgb(raindrop.hue, 0.8, 1.0);
-        circuit.set_led(raindrop.position, head_color, Priority::Normal);
+        let head_color = Color::from_hsv(raindrop.hue, self.style.saturation, 1.0);
+        circuit.set_led(position, head_color, Priority::Normal);
 
         // Render the tail with fading brightness
         for i in 1..=raindrop.tail_length {
-            let pos = (led_count + raindrop.position - i) % led_count;
+            let pos = (led_count + position - i) % led_count;
             let fade_factor = 1.0 - (i as f32 / (raindrop.tail_length as f32 + 1.0));
-            let tail_color = self.hsv_to_rgb(
+            let tail_color = Color::from_hsv(
                 raindrop.hue,
-                0.8,
-                fade_factor * 0.8, // Fade out brightness
+                self.style.saturation,
+                fade_factor * 0.9, // Fade out brightness but keep it visible
             );
             circuit.set_led(pos, tail_color, Priority::Normal);
         }
@@ -206,104 +220,39 @@ impl RainDropRace {
 }
 
 impl Animation for RainDropRace {
-    fn reset(&self) {
-        self.start_time.set(Duration::from_millis(0));
-        self.last_update.set(Duration::from_millis(0));
-        self.last_raindrop.set(Duration::from_millis(0));
-        self.last_flash.set(Duration::from_millis(0));
-        self.flash_active.set(false);
+    fn reset(&mut self) {
+        self.raindrops.clear();
+        self.elapsed = Duration::from_millis(0);
+        self.since_raindrop = Duration::from_millis(0);
+        self.spawn_interval = Duration::from_millis(500);
+        self.since_flash = Duration::from_millis(0);
+        self.flash_interval = Duration::from_secs(5);
+        self.flash_active = false;
+        self.rng = Rng::new(self.seed);
     }
 
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
-        // Initialize start time if this is the first render
-        if self.start_time.get().as_millis() == 0 {
-            self.start_time.set(timestamp);
-            self.last_update.set(timestamp);
-            self.last_raindrop.set(timestamp);
-        }
-
-        // Since we can't mutate self directly, we'll update the Cell values and
-        // render based on the current state
+    fn update<const N: usize, C: Circuit<N>>(&mut self, circuit: &C, dt: Duration) {
+        let led_count = circuit.led_count();
 
-        // Check for lightning flash
-        let lightning_active = self.check_lightning(timestamp);
+        self.elapsed += dt;
+        self.check_lightning(dt);
+        self.update_raindrops(dt, led_count);
+        self.check_spawn_raindrop(dt, led_count);
+    }
 
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
         // If lightning is active, flash the entire circuit
-        if lightning_active {
+        if self.flash_active {
             // Create a bright white flash
             let flash_color = Color(255, 255, 255);
             for i in 0..circuit.led_count() {
                 circuit.set_led(i, flash_color, Priority::Normal);
             }
         } else {
-            // Create a static set of raindrops for demonstration
-            // Since we can't modify self.raindrops directly in the embedded context,
-            // we'll create raindrops on the fly based on the current time
-
-            // Use seconds instead of microseconds for a more stable hash
-            // This will only change once per second instead of constantly
-            let time_hash = timestamp.as_secs() as u32;
-            let led_count = circuit.led_count();
-
-            // Create 8 raindrops with different positions and colors
-            for i in 0..8 {
-                // Use a different seed for each raindrop, but keep it stable
-                // by using a fixed offset rather than time-based randomization
-                let seed = 12345 + i as u32 * 789;
-
-                // Make the raindrops move at a visible but gentle pace
-                let speed = 3.0 + (i as f32 * 0.5); // 3-6.5 LEDs per second
-
-                // Calculate position based on time and speed to create movement
-                // Use a fixed starting position for each raindrop
-                let base_position = (30 * i) % led_count; // Evenly space raindrops around the circuit
-
-                // Use the raw timestamp without dividing by 10 to allow visible movement
-                let elapsed_secs = timestamp.as_secs() as f32;
-                let position = (base_position + (elapsed_secs * speed) as usize) % led_count;
-
-                // Use a different hue for each raindrop, but make them more blue/teal like rain
-                // Fixed hues for stability, no cycling
-                let hue = 0.5 + (i as f32 * 0.02); // 0.5-0.64 range (blue to teal)
-
-                // Create a raindrop at this position with a softer color
-                let head_color = self.hsv_to_rgb(hue, 0.7, 1.0); // Less saturation for softer look
-                circuit.set_led(position, head_color, Priority::Normal);
-
-                // Create a longer, more visible tail
-                let tail_length = 3 + (i % 4) as usize; // 3-6 LEDs for tail (shorter)
-                for j in 1..=tail_length {
-                    let pos = (led_count + position - j) % led_count;
-                    let fade_factor = 1.0 - (j as f32 / (tail_length as f32 + 1.0));
-
-                    // Keep the same hue but reduce brightness for the tail
-                    let tail_color = self.hsv_to_rgb(
-                        hue,
-                        0.7,
-                        fade_factor * 0.9, // Fade out brightness but keep it visible
-                    );
-                    circuit.set_led(pos, tail_color, Priority::Normal);
-                }
+            for raindrop in &self.raindrops {
+                self.render_raindrop(circuit, raindrop);
             }
         }
-
-        // Update state for next frame
-        self.last_update.set(timestamp);
-
-        // Check if it's time to spawn a new raindrop
-        let last_spawn = self.last_raindrop.get();
-        let interval = self.spawn_interval.get();
-
-        if timestamp - last_spawn >= interval {
-            // Update the last raindrop time
-            self.last_raindrop.set(timestamp);
-
-            // Set next spawn interval (500-2000ms)
-            let time_hash = timestamp.as_micros() as u32;
-            let next_interval = 500 + (time_hash % 1500);
-            self.spawn_interval
-                .set(Duration::from_millis(next_interval as u64));
-        }
     }
 
     fn is_finished(&self) -> bool {
@@ -312,8 +261,7 @@ impl Animation for RainDropRace {
             false
         } else {
             // Check if we've exceeded the duration
-            let elapsed = self.last_update.get() - self.start_time.get();
-            elapsed >= self.duration
+            self.elapsed >= self.duration
         }
     }
 
@@ -0,0 +1,430 @@
+use embassy_time::Duration;
+use heapless::Vec as HeaplessVec;
+
+use crate::{Circuit, Priority};
+
+use super::{compositor::compose, Animation, Layer};
+
+/// How a value moves from one keyframe to the next
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    /// Start slow, end fast
+    EaseIn,
+    /// Start fast, end slow
+    EaseOut,
+    /// Start and end slow
+    EaseInOut,
+    /// Keep the previous value until the keyframe is reached
+    Hold,
+}
+
+impl Easing {
+    /// Map `t` from 0.0 to 1.0 onto the eased progress from 0.0 to 1.0
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::Hold => {
+                if t < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+/// What a cue does to its track
+#[allow(clippy::large_enum_variant)] // No allocator to box the animation, cues are few
+pub enum CueAction {
+    /// Play an animation on the track from its first frame, replacing what the track was playing
+    Start(Layer),
+    /// Stop the animation of the track
+    Stop,
+    /// Change the opacity of the track, from what it is when the cue is reached
+    Fade {
+        opacity: u8,
+        duration: Duration,
+        easing: Easing,
+    },
+}
+
+/// An action on a track of a [`Timeline`] at a point in the show
+pub struct Cue {
+    pub at: Duration,
+    pub track: usize,
+    pub action: CueAction,
+}
+
+impl Cue {
+    pub const fn start(at: Duration, track: usize, layer: Layer) -> Self {
+        Self {
+            at,
+            track,
+            action: CueAction::Start(layer),
+        }
+    }
+
+    pub const fn stop(at: Duration, track: usize) -> Self {
+        Self {
+            at,
+            track,
+            action: CueAction::Stop,
+        }
+    }
+
+    pub const fn fade(
+        at: Duration,
+        track: usize,
+        opacity: u8,
+        duration: Duration,
+        easing: Easing,
+    ) -> Self {
+        Self {
+            at,
+            track,
+            action: CueAction::Fade {
+                opacity,
+                duration,
+                easing,
+            },
+        }
+    }
+}
+
+/// Track parameters that can be keyframed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Param {
+    /// How fast the animation runs, 1.0 is normal speed
+    Speed,
+    /// Scale of the colors the animation renders, 1.0 is unchanged
+    Brightness,
+}
+
+/// Value of a track parameter at a point in the show. Between keyframes the value moves with the
+/// easing of the later keyframe, before the first and after the last it holds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    pub at: Duration,
+    pub track: usize,
+    pub param: Param,
+    pub value: f32,
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    pub const fn new(at: Duration, track: usize, param: Param, value: f32) -> Self {
+        Self {
+            at,
+            track,
+            param,
+            value,
+            easing: Easing::Linear,
+        }
+    }
+
+    pub const fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Fade {
+    from: f32,
+    to: f32,
+    start: Duration,
+    duration: Duration,
+    easing: Easing,
+}
+
+/// Playback state of a track
+#[derive(Clone, Copy)]
+struct Track {
+    /// Index of the start cue holding the animation that is playing
+    cue: Option<usize>,
+    /// 0.0 to 1.0
+    opacity: f32,
+    fade: Option<Fade>,
+}
+
+impl Track {
+    const STOPPED: Self = Self {
+        cue: None,
+        opacity: 1.0,
+        fade: None,
+    };
+}
+
+/// A scripted show of animations on `T` tracks, with up to `C` cues and `K` keyframes.
+///
+/// Tracks are blended like the layers of a [`Compositor`](super::Compositor), from track 0 at the
+/// bottom to the top. The show runs on the time passed to `update`, so it plays the same on every
+/// run.
+pub struct Timeline<const T: usize, const C: usize, const K: usize> {
+    cues: HeaplessVec<Cue, C>,
+    keyframes: HeaplessVec<Keyframe, K>,
+    tracks: [Track; T],
+    /// Length of the show
+    length: Duration,
+    /// Time since the start of the show
+    time: Duration,
+    /// Index of the first cue that hasn't been reached
+    next_cue: usize,
+}
+
+impl<const T: usize, const C: usize, const K: usize> Timeline<T, C, K> {
+    pub const fn new(length: Duration) -> Self {
+        Self {
+            cues: HeaplessVec::new(),
+            keyframes: HeaplessVec::new(),
+            tracks: [Track::STOPPED; T],
+            length,
+            time: Duration::from_ticks(0),
+            next_cue: 0,
+        }
+    }
+
+    /// Add a cue, cues at the same time run in the order they were added
+    pub fn add_cue(&mut self, cue: Cue) {
+        let index = self.cues.iter().take_while(|c| c.at <= cue.at).count();
+        self.cues.insert(index, cue).ok(); // Ignore if full
+    }
+
+    pub fn add_keyframe(&mut self, keyframe: Keyframe) {
+        self.keyframes.push(keyframe).ok(); // Ignore if full
+    }
+
+    /// Time since the start of the show
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Value of a track parameter at the current time, 1.0 without keyframes
+    fn param(&self, track: usize, param: Param) -> f32 {
+        let mut before: Option<&Keyframe> = None;
+        let mut after: Option<&Keyframe> = None;
+        for keyframe in self
+            .keyframes
+            .iter()
+            .filter(|k| k.track == track && k.param == param)
+        {
+            if keyframe.at <= self.time {
+                if before.is_none_or(|b| keyframe.at >= b.at) {
+                    before = Some(keyframe);
+                }
+            } else if after.is_none_or(|a| keyframe.at < a.at) {
+                after = Some(keyframe);
+            }
+        }
+
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                let t = (self.time - before.at).as_micros() as f32
+                    / (after.at - before.at).as_micros() as f32;
+                before.value + (after.value - before.value) * after.easing.apply(t)
+            }
+            (Some(keyframe), None) | (None, Some(keyframe)) => keyframe.value,
+            (None, None) => 1.0,
+        }
+    }
+
+    fn run_cue(&mut self, index: usize) {
+        let cue = &mut self.cues[index];
+        let Some(track) = self.tracks.get_mut(cue.track) else {
+            return;
+        };
+
+        match &mut cue.action {
+            CueAction::Start(layer) => {
+                layer.animation.reset();
+                *track = Track {
+                    cue: Some(index),
+                    opacity: layer.opacity as f32 / 255.0,
+                    fade: None,
+                };
+            }
+            CueAction::Stop => *track = Track::STOPPED,
+            CueAction::Fade {
+                opacity,
+                duration,
+                easing,
+            } => {
+                track.fade = Some(Fade {
+                    from: track.opacity,
+                    to: *opacity as f32 / 255.0,
+                    start: cue.at,
+                    duration: *duration,
+                    easing: *easing,
+                })
+            }
+        }
+    }
+}
+
+impl<const T: usize, const C: usize, const K: usize> Animation for Timeline<T, C, K> {
+    fn update<const N: usize, CI: Circuit<N>>(&mut self, circuit: &CI, dt: Duration) {
+        self.time += dt;
+
+        while self.next_cue < self.cues.len() && self.cues[self.next_cue].at <= self.time {
+            self.run_cue(self.next_cue);
+            self.next_cue += 1;
+        }
+
+        for index in 0..T {
+            let speed = self.param(index, Param::Speed).max(0.0);
+            let track = &mut self.tracks[index];
+
+            if let Some(fade) = track.fade {
+                let t = (self.time - fade.start).as_micros() as f32
+                    / fade.duration.as_micros().max(1) as f32;
+                track.opacity = fade.from + (fade.to - fade.from) * fade.easing.apply(t);
+                if t >= 1.0 {
+                    track.fade = None;
+                }
+            }
+
+            if let Some(cue) = track.cue {
+                if let CueAction::Start(layer) = &mut self.cues[cue].action {
+                    let dt = Duration::from_micros((dt.as_micros() as f32 * speed) as u64);
+                    layer.animation.update(circuit, dt);
+                }
+            }
+        }
+    }
+
+    fn render<const N: usize, CI: Circuit<N>>(&self, circuit: &mut CI) {
+        let layers = self.tracks.iter().enumerate().filter_map(|(index, track)| {
+            let CueAction::Start(layer) = &self.cues[track.cue?].action else {
+                return None;
+            };
+            let opacity = (track.opacity.clamp(0.0, 1.0) * 255.0) as u8;
+            Some((layer, opacity, self.param(index, Param::Brightness)))
+        });
+        compose(circuit, layers);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.time >= self.length
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Normal
+    }
+
+    fn reset(&mut self) {
+        self.time = Duration::from_ticks(0);
+        self.next_cue = 0;
+        self.tracks = [Track::STOPPED; T];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::{Animations, BlendMode, GhostCar, StaticColor},
+        zandvoort::Zandvoort,
+        Color,
+    };
+
+    const RED: Color = Color(200, 0, 0);
+    const BLUE: Color = Color(0, 0, 200);
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    /// Render the show at each of `times`, returning the color of the first LED
+    fn render_at<const T: usize, const C: usize, const K: usize>(
+        show: &mut Timeline<T, C, K>,
+        times: &[Duration],
+    ) -> [Color; 4] {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut colors = [Color(0, 0, 0); 4];
+        for (color, &time) in colors.iter_mut().zip(times) {
+            show.update(&zandvoort, time - show.time());
+            zandvoort.led_buffer().clear();
+            show.render(&mut zandvoort);
+            *color = zandvoort.led_buffer().get_colors()[0].0;
+        }
+        colors
+    }
+
+    #[test]
+    fn cues_start_fade_and_stop() {
+        let mut show: Timeline<2, 4, 0> = Timeline::new(secs(20));
+        let red = Layer::new(Animations::Static(StaticColor::new(RED)));
+        let blue = Layer::new(Animations::Static(StaticColor::new(BLUE)));
+        show.add_cue(Cue::start(secs(0), 0, red));
+        show.add_cue(Cue::start(secs(10), 1, blue));
+        show.add_cue(Cue::fade(secs(10), 1, 0, secs(4), Easing::Linear));
+        show.add_cue(Cue::stop(secs(15), 0));
+
+        let colors = render_at(&mut show, &[secs(5), secs(10), secs(12), secs(16)]);
+        assert_eq!(colors[0], RED);
+        assert_eq!(colors[1], BLUE);
+        assert_eq!(colors[2], BlendMode::AlphaOver.blend(RED, BLUE, 127));
+        assert_eq!(colors[3], Color(0, 0, 0));
+        assert!(!show.is_finished());
+
+        // A reset show plays the same again
+        show.reset();
+        let again = render_at(&mut show, &[secs(5), secs(10), secs(12), secs(16)]);
+        assert_eq!(again, colors);
+    }
+
+    #[test]
+    fn keyframes_ease_params() {
+        let mut show: Timeline<1, 1, 3> = Timeline::new(secs(20));
+        let red = Layer::new(Animations::Static(StaticColor::new(RED)));
+        show.add_cue(Cue::start(secs(0), 0, red));
+        show.add_keyframe(Keyframe::new(secs(0), 0, Param::Brightness, 0.0));
+        show.add_keyframe(
+            Keyframe::new(secs(10), 0, Param::Brightness, 1.0).with_easing(Easing::EaseIn),
+        );
+        show.add_keyframe(
+            Keyframe::new(secs(12), 0, Param::Brightness, 0.5).with_easing(Easing::Hold),
+        );
+
+        let colors = render_at(&mut show, &[secs(5), secs(10), secs(11), secs(12)]);
+        assert_eq!(colors, [Color(50, 0, 0), RED, RED, Color(100, 0, 0)]);
+    }
+
+    #[test]
+    fn speed_keyframes_scale_animation_time() {
+        let mut normal: Timeline<1, 1, 0> = Timeline::new(secs(20));
+        normal.add_cue(Cue::start(
+            secs(0),
+            0,
+            Layer::new(Animations::GhostCar(GhostCar::new(20.0, 4, RED))),
+        ));
+        let mut double: Timeline<1, 1, 1> = Timeline::new(secs(20));
+        double.add_cue(Cue::start(
+            secs(0),
+            0,
+            Layer::new(Animations::GhostCar(GhostCar::new(10.0, 4, RED))),
+        ));
+        double.add_keyframe(Keyframe::new(secs(0), 0, Param::Speed, 2.0));
+
+        assert_eq!(
+            frame_after(&mut normal, secs(3)),
+            frame_after(&mut double, secs(3))
+        );
+    }
+
+    fn frame_after<const T: usize, const C: usize, const K: usize>(
+        show: &mut Timeline<T, C, K>,
+        dt: Duration,
+    ) -> [Color; 216] {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        show.update(&zandvoort, dt);
+        show.render(&mut zandvoort);
+        core::array::from_fn(|i| zandvoort.led_buffer().get_colors()[i].0)
+    }
+}
@@ -0,0 +1,130 @@
+use embassy_time::Duration;
+
+use crate::{Circuit, Color, Point, Priority};
+
+use super::BlendMode;
+
+const OFF: Color = Color(0, 0, 0);
+
+/// Direction a [`Transition::Wipe`] sweeps over the circuit
+#[derive(Clone, Copy, Debug, PartialEq, bincode::Encode, bincode::Decode)]
+pub enum Wipe {
+    /// From the first to the last LED along the track
+    TrackOrder,
+    LeftToRight,
+    RightToLeft,
+    BottomToTop,
+    TopToBottom,
+}
+
+impl Wipe {
+    /// Where LED `index` is along the wipe, from 0.0 (revealed first) to 1.0 (revealed last)
+    fn position<const N: usize, C: Circuit<N>>(&self, circuit: &C, index: usize) -> f32 {
+        let led_count = circuit.led_count();
+        if index >= led_count {
+            return 0.0;
+        }
+        let Point { x, y } = circuit.geometry().normalized(index);
+
+        match self {
+            Wipe::TrackOrder => index as f32 / led_count as f32,
+            Wipe::LeftToRight => x,
+            Wipe::RightToLeft => 1.0 - x,
+            Wipe::BottomToTop => y,
+            Wipe::TopToBottom => 1.0 - y,
+        }
+    }
+}
+
+/// How the queue switches from one animation to the next
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transition {
+    /// Switch at once
+    Cut,
+    /// Blend the outgoing animation into the incoming one
+    Crossfade(Duration),
+    /// Fade the outgoing animation out, then the incoming one in
+    FadeThroughBlack(Duration),
+    /// Reveal the incoming animation LED by LED
+    Wipe(Wipe, Duration),
+}
+
+impl Transition {
+    pub fn duration(&self) -> Duration {
+        match *self {
+            Transition::Cut => Duration::from_ticks(0),
+            Transition::Crossfade(duration)
+            | Transition::FadeThroughBlack(duration)
+            | Transition::Wipe(_, duration) => duration,
+        }
+    }
+
+    /// Render the mix of the outgoing `from` and incoming `to` frames into the LED buffer of the
+    /// circuit, at `progress` from 0.0 (all `from`) to 1.0 (all `to`)
+    pub fn render<const N: usize, C: Circuit<N>>(
+        &self,
+        circuit: &mut C,
+        from: &[Color; N],
+        to: &[Color; N],
+        progress: f32,
+    ) {
+        let progress = progress.clamp(0.0, 1.0);
+
+        for (i, (&from, &to)) in from.iter().zip(to).enumerate() {
+            let color = match *self {
+                Transition::Cut => to,
+                Transition::Crossfade(_) => BlendMode::Replace.blend(from, to, opacity(progress)),
+                Transition::FadeThroughBlack(_) if progress < 0.5 => {
+                    BlendMode::Replace.blend(from, OFF, opacity(progress * 2.0))
+                }
+                Transition::FadeThroughBlack(_) => {
+                    BlendMode::Replace.blend(OFF, to, opacity(progress * 2.0 - 1.0))
+                }
+                Transition::Wipe(wipe, _) if wipe.position(circuit, i) < progress => to,
+                Transition::Wipe(..) => from,
+            };
+            circuit.led_buffer().set_color(i, color, Priority::Normal);
+        }
+    }
+}
+
+fn opacity(progress: f32) -> u8 {
+    (progress * 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zandvoort::Zandvoort;
+
+    const RED: Color = Color(200, 0, 0);
+    const BLUE: Color = Color(0, 0, 200);
+
+    #[test]
+    fn fade_through_black_and_wipe() {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let from = [RED; 216];
+        let to = [BLUE; 216];
+
+        let fade = Transition::FadeThroughBlack(Duration::from_millis(100));
+        fade.render(&mut zandvoort, &from, &to, 0.5);
+        assert_eq!(zandvoort.led_buffer().get_colors()[0].0, OFF);
+
+        let wipe = Transition::Wipe(Wipe::TrackOrder, Duration::from_millis(100));
+        wipe.render(&mut zandvoort, &from, &to, 0.5);
+        let colors = zandvoort.led_buffer().get_colors();
+        assert_eq!(colors[0].0, BLUE);
+        assert_eq!(colors[215].0, RED);
+
+        // The left most LED is revealed first
+        let wipe = Transition::Wipe(Wipe::LeftToRight, Duration::from_millis(100));
+        wipe.render(&mut zandvoort, &from, &to, 0.01);
+        let positions = zandvoort.led_positions();
+        let left = (0..216)
+            .min_by(|&a, &b| positions[a].x.total_cmp(&positions[b].x))
+            .unwrap();
+        let colors = zandvoort.led_buffer().get_colors();
+        assert_eq!(colors[left].0, BLUE);
+        assert!(colors.iter().filter(|(c, _)| *c == BLUE).count() < 20);
+    }
+}
@@ -1,44 +1,68 @@
-use core::cell::Cell;
 use embassy_time::Duration;
-use libm::sinf;
 
-use crate::{animation::Animation, Circuit, Color, Priority};
+use crate::{
+    animation::Animation,
+    color::Palette,
+    fixed::{lerp, turns, Q16},
+    Circuit, Color, Priority,
+};
 
+/// The palette as bands sliding along the x axis, looping from the last color back to the first
 pub struct UnicornRainbow {
     speed: f32,
-    phase: Cell<f32>,
+    phase: f32,
     wavelength: f32,
+    palette: Palette,
 }
 
-unsafe impl Sync for UnicornRainbow {}
-
 impl UnicornRainbow {
     pub const fn new(speed: f32, wavelength: f32) -> Self {
         Self {
             speed,
-            phase: Cell::new(0.0),
+            phase: 0.0,
             wavelength,
+            palette: Palette::RAINBOW,
         }
     }
+
+    pub const fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Color at `angle` of the palette loop
+    fn color_at(&self, angle: u16) -> Color {
+        let scaled = angle as u32 * self.palette.len() as u32;
+        let index = (scaled >> 16) as usize;
+        let t = (scaled & 0xffff) as Q16;
+        let from = self.palette.color(index);
+        let to = self.palette.color(index + 1);
+        let channel = |a: u8, b: u8| lerp(a as Q16, b as Q16, t) as u8;
+        Color(
+            channel(from.0, to.0),
+            channel(from.1, to.1),
+            channel(from.2, to.2),
+        )
+    }
 }
 
 impl Animation for UnicornRainbow {
-    fn reset(&self) {
-        self.phase.set(0.0);
+    fn reset(&mut self) {
+        self.phase = 0.0;
     }
 
-    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C, timestamp: Duration) {
-        let t = timestamp.as_micros() as f32 * 1e-6;
-        let phase_shift = self.speed * t;
-        self.phase.set(self.phase.get() + phase_shift);
+    fn update<const N: usize, C: Circuit<N>>(&mut self, _circuit: &C, dt: Duration) {
+        let dt = dt.as_micros() as f32 * 1e-6;
+        self.phase = (self.phase + self.speed * dt) % 1.0;
+    }
 
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        let offset = turns(self.phase);
+        let step = 65536.0 / self.wavelength;
         for i in 0..N {
             let position = circuit.led_positions()[i];
-            let phase = (position.x / self.wavelength + self.phase.get()) % 1.0;
-            let red = ((sinf(phase * core::f32::consts::PI * 2.0) + 1.0) * 127.5) as u8;
-            let green = ((sinf((phase + 0.33) * core::f32::consts::PI * 2.0) + 1.0) * 127.5) as u8;
-            let blue = ((sinf((phase + 0.66) * core::f32::consts::PI * 2.0) + 1.0) * 127.5) as u8;
-            circuit.set_led(i, Color(red, green, blue), Priority::Normal);
+            let angle = ((position.x * step) as i32 as u16).wrapping_add(offset);
+            circuit.set_led(i, self.color_at(angle), Priority::Normal);
         }
     }
 
@@ -0,0 +1,201 @@
+//! Circuits generated by `build.rs` from the descriptors in `circuits/`, one module per file.
+//!
+//! A descriptor holds the name of the circuit, the LED positions in track order, where the
+//! sectors start and the seed of the random LED order. It may also name the start/finish LED,
+//! the corners, the DRS zones and the pit lane. See `circuits/zandvoort.circuit`.
+
+/// Defines a circuit from the data of a descriptor
+macro_rules! circuit {
+    (
+        $(#[$meta:meta])*
+        $circuit:ident,
+        name: $name:literal,
+        seed: $seed:literal,
+        sectors: [$sector_1:literal, $sector_2:literal, $sector_3:literal],
+        positions: [$(($x:literal, $y:literal)),* $(,)?],
+        start_finish: $start_finish:literal,
+        corners: [$(($corner:literal, $corner_first:literal, $corner_last:literal)),* $(,)?],
+        drs_zones: [$(($detection:literal, $drs_first:literal, $drs_last:literal)),* $(,)?],
+        pit_lane: $pit_lane:expr $(,)?
+    ) => {
+        use crate::{
+            geometry::CircuitGeometry,
+            rng::Rng,
+            track::{Corner, DrsZone, PitLane},
+            Circuit, Color, LedStateBuffer, Point, Priority, Sector,
+        };
+
+        pub const LED_COUNT: usize = [$($x),*].len();
+
+        /// LED positions in sorted order, following the physical layout of the circuit
+        pub const LED_POSITIONS_SORTED: [Point; LED_COUNT] = [$(Point::new($x, $y)),*];
+
+        /// LED indices in a random order, the same on every build
+        pub const LED_ORDER_RANDOM: [usize; LED_COUNT] = {
+            let mut order = [0; LED_COUNT];
+            let mut i = 0;
+            while i < LED_COUNT {
+                order[i] = i;
+                i += 1;
+            }
+            let mut rng = Rng::new($seed);
+            rng.shuffle(&mut order);
+            order
+        };
+
+        /// The positions in the random order of `LED_ORDER_RANDOM`
+        pub const LED_POSITIONS_RANDOM: [Point; LED_COUNT] = {
+            let mut positions = LED_POSITIONS_SORTED;
+            let mut i = 0;
+            while i < LED_COUNT {
+                positions[i] = LED_POSITIONS_SORTED[LED_ORDER_RANDOM[i]];
+                i += 1;
+            }
+            positions
+        };
+
+        /// First LED of every sector, and the end of the last one
+        const SECTOR_STARTS: [usize; 4] = [$sector_1, $sector_2, $sector_3, LED_COUNT];
+
+        pub const CORNERS: &[Corner] = &[$(Corner {
+            name: $corner,
+            segment: crate::track::Segment::new($corner_first, $corner_last),
+        }),*];
+
+        pub const DRS_ZONES: &[DrsZone] = &[$(DrsZone {
+            detection: $detection,
+            activation: crate::track::Segment::new($drs_first, $drs_last),
+        }),*];
+
+        pub const PIT_LANE: Option<PitLane> = match $pit_lane {
+            Some((entry, exit)) => Some(PitLane::new(entry, exit)),
+            None => None,
+        };
+
+        $(#[$meta])*
+        pub struct $circuit<const N: usize> {
+            buffer: LedStateBuffer<N>,
+            geometry: CircuitGeometry<N>,
+        }
+
+        impl<const N: usize> $circuit<N> {
+            pub fn new() -> Self {
+                Self {
+                    buffer: LedStateBuffer::new(),
+                    geometry: CircuitGeometry::new(&LED_POSITIONS_SORTED),
+                }
+            }
+        }
+
+        impl<const N: usize> Default for $circuit<N> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<const N: usize> Circuit<N> for $circuit<N> {
+            const LED_COUNT: usize = LED_COUNT;
+
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn led_positions_random(&self) -> &'static [Point] {
+                &LED_POSITIONS_RANDOM
+            }
+
+            fn led_order_random(&self) -> &'static [usize] {
+                &LED_ORDER_RANDOM
+            }
+
+            fn led_positions(&self) -> &'static [Point] {
+                &LED_POSITIONS_SORTED
+            }
+
+            fn led_count(&self) -> usize {
+                Self::LED_COUNT
+            }
+
+            fn sectors(&self, sector: Sector) -> &'static [Point] {
+                &self.led_positions()[self.sector_indices(sector)]
+            }
+
+            fn sector_indices(&self, sector: Sector) -> core::ops::Range<usize> {
+                let index = sector as usize;
+                SECTOR_STARTS[index]..SECTOR_STARTS[index + 1]
+            }
+
+            fn start_finish(&self) -> usize {
+                $start_finish
+            }
+
+            fn corners(&self) -> &'static [Corner] {
+                CORNERS
+            }
+
+            fn drs_zones(&self) -> &'static [DrsZone] {
+                DRS_ZONES
+            }
+
+            fn pit_lane(&self) -> Option<PitLane> {
+                PIT_LANE
+            }
+
+            fn led_buffer(&mut self) -> &mut LedStateBuffer<N> {
+                &mut self.buffer
+            }
+
+            fn set_led(&mut self, index: usize, color: Color, priority: Priority) {
+                self.buffer.set_led(index, color, priority);
+            }
+
+            fn geometry(&self) -> &CircuitGeometry<N> {
+                &self.geometry
+            }
+        }
+    };
+}
+
+include!(concat!(env!("OUT_DIR"), "/circuits.rs"));
+
+#[cfg(test)]
+mod tests {
+    use super::{spa, zandvoort};
+    use crate::{Circuit, Sector};
+
+    /// The sectors follow each other and hold every LED once
+    fn assert_sectors_cover<const N: usize>(circuit: &impl Circuit<N>) {
+        let mut next = 0;
+        for sector in [Sector::_1, Sector::_2, Sector::_3] {
+            let indices = circuit.sector_indices(sector);
+            assert_eq!(indices.start, next, "{}", circuit.name());
+            assert!(!indices.is_empty(), "{}", circuit.name());
+            assert_eq!(circuit.sector_of(indices.end - 1), sector);
+            next = indices.end;
+        }
+        assert_eq!(next, circuit.led_count(), "{}", circuit.name());
+    }
+
+    #[test]
+    fn sectors_cover_the_circuit() {
+        assert_sectors_cover(&zandvoort::Zandvoort::<{ zandvoort::LED_COUNT }>::new());
+        assert_sectors_cover(&spa::Spa::<{ spa::LED_COUNT }>::new());
+    }
+
+    #[test]
+    fn random_order_shuffles_every_led_once() {
+        let circuit = zandvoort::Zandvoort::<{ zandvoort::LED_COUNT }>::new();
+        let order = circuit.led_order_random();
+        let mut seen = [false; zandvoort::LED_COUNT];
+        for (i, &led) in order.iter().enumerate() {
+            assert!(!seen[led]);
+            seen[led] = true;
+            let (shuffled, position) = (
+                circuit.led_positions_random()[i],
+                circuit.led_positions()[led],
+            );
+            assert_eq!((shuffled.x, shuffled.y), (position.x, position.y));
+        }
+        assert_ne!(order[..10], [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+}
@@ -0,0 +1,122 @@
+//! Where animations get their time from. The firmware reads the embassy time driver, tests and
+//! offline renders step a clock by hand, so the same code runs without real time passing.
+
+use embassy_time::{Duration, Instant};
+
+/// Source of the current time
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// Time of the embassy time driver, only available where a driver is linked in
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock that only moves when it is told to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ManualClock {
+    now: Instant,
+}
+
+impl ManualClock {
+    pub const fn new() -> Self {
+        Self {
+            now: Instant::from_ticks(0),
+        }
+    }
+
+    pub fn advance(&mut self, dt: Duration) {
+        self.now += dt;
+    }
+
+    pub fn set(&mut self, now: Instant) {
+        self.now = now;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now
+    }
+}
+
+/// Turns readings of a clock into the time between frames, for `Animation::update`
+pub struct FrameClock<C: Clock> {
+    clock: C,
+    last_frame: Option<Instant>,
+}
+
+impl<C: Clock> FrameClock<C> {
+    pub const fn new(clock: C) -> Self {
+        Self {
+            clock,
+            last_frame: None,
+        }
+    }
+
+    /// Time since the previous tick, zero on the first tick
+    pub fn tick(&mut self) -> Duration {
+        let now = self.clock.now();
+        let dt = self.last_frame.map_or(Duration::from_ticks(0), |last| {
+            now.saturating_duration_since(last)
+        });
+        self.last_frame = Some(now);
+        dt
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    pub fn clock_mut(&mut self) -> &mut C {
+        &mut self.clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::{AnimationQueue, Animations, StaticColor},
+        zandvoort::Zandvoort,
+        Circuit, Color,
+    };
+
+    #[test]
+    fn manual_clock_drives_a_queue() {
+        const RED: Color = Color(255, 0, 0);
+        const BLUE: Color = Color(0, 0, 255);
+
+        let mut frames = FrameClock::new(ManualClock::new());
+        assert_eq!(frames.tick(), Duration::from_ticks(0));
+        frames.clock_mut().advance(Duration::from_millis(40));
+        assert_eq!(frames.tick(), Duration::from_millis(40));
+
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut queue: AnimationQueue = AnimationQueue::new(Duration::from_secs(60));
+        queue.add_animation(Animations::Static(StaticColor::new(RED)));
+        queue.add_animation(Animations::Static(StaticColor::new(BLUE)));
+
+        // A minute goes by without waiting for it
+        let mut clock = ManualClock::new();
+        let mut color_at = |seconds| {
+            clock.set(Instant::from_secs(seconds));
+            queue.render(&mut zandvoort, clock.now());
+            zandvoort.led_buffer().get_colors()[0].0
+        };
+        assert_eq!(color_at(0), RED);
+        assert_eq!(color_at(59), RED);
+        assert_eq!(color_at(61), BLUE);
+    }
+}
@@ -0,0 +1,316 @@
+//! Colors, conversions between color spaces, gradients and palettes.
+
+use libm::{cbrtf, floorf, powf};
+
+#[derive(Clone, Copy, Debug, PartialEq, bincode::Encode, bincode::Decode)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const BLACK: Color = Color(0, 0, 0);
+    pub const WHITE: Color = Color(255, 255, 255);
+    pub const RED: Color = Color(255, 0, 0);
+    pub const GREEN: Color = Color(0, 255, 0);
+    pub const BLUE: Color = Color(0, 0, 255);
+    /// Dutch orange, as bright as the LEDs show it
+    pub const ORANGE: Color = Color(255, 20, 0);
+
+    /// h: 0.0 - 1.0 (hue, wraps around)
+    /// s: 0.0 - 1.0 (saturation)
+    /// v: 0.0 - 1.0 (value/brightness)
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Color {
+        let h = h - floorf(h);
+        let h6 = h * 6.0;
+        let i = h6 as u8;
+        let f = h6 - i as f32;
+
+        let p = v * (1.0 - s);
+        let q = v * (1.0 - s * f);
+        let t = v * (1.0 - s * (1.0 - f));
+
+        let (r, g, b) = match i {
+            0 => (v, t, p),
+            1 => (q, v, p),
+            2 => (p, v, t),
+            3 => (p, q, v),
+            4 => (t, p, v),
+            _ => (v, p, q),
+        };
+
+        Color(channel(r), channel(g), channel(b))
+    }
+
+    /// h: 0.0 - 1.0 (hue, wraps around)
+    /// s: 0.0 - 1.0 (saturation)
+    /// l: 0.0 - 1.0 (lightness, 0.5 is the pure color)
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Color {
+        let v = l + s * l.min(1.0 - l);
+        let s = if v == 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+        Color::from_hsv(h, s, v)
+    }
+
+    /// Scale by `brightness` from 0.0 (off) to 1.0 (unchanged)
+    pub fn scale(self, brightness: f32) -> Color {
+        let scale = |c: u8| (c as f32 * brightness).min(255.0) as u8;
+        Color(scale(self.0), scale(self.1), scale(self.2))
+    }
+
+    /// Like `scale`, keeping the fraction of a step as 8.8 fixed point channels for
+    /// `Circuit::set_led_fine`
+    pub fn scale_fine(self, brightness: f32) -> [u16; 3] {
+        let scale = |c: u8| (c as f32 * brightness * 256.0).clamp(0.0, 65535.0) as u16;
+        [scale(self.0), scale(self.1), scale(self.2)]
+    }
+
+    /// Linear interpolation, `t` from 0.0 (self) to 1.0 (other)
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t + 0.5) as u8;
+        Color(
+            lerp(self.0, other.0),
+            lerp(self.1, other.1),
+            lerp(self.2, other.2),
+        )
+    }
+
+    /// Interpolation through OKLab, which keeps the brightness even halfway between two hues
+    pub fn lerp_oklab(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let (a, b) = (Oklab::from(self), Oklab::from(other));
+        Oklab {
+            l: a.l + (b.l - a.l) * t,
+            a: a.a + (b.a - a.a) * t,
+            b: a.b + (b.b - a.b) * t,
+        }
+        .into()
+    }
+}
+
+fn channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0) as u8
+}
+
+/// Perceptual color space, see <https://bottosson.github.io/posts/oklab/>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklab {
+    /// Lightness, 0.0 - 1.0
+    pub l: f32,
+    /// Green to red
+    pub a: f32,
+    /// Blue to yellow
+    pub b: f32,
+}
+
+fn to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        powf((c + 0.055) / 1.055, 2.4)
+    }
+}
+
+fn from_linear(c: f32) -> u8 {
+    let c = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * powf(c, 1.0 / 2.4) - 0.055
+    };
+    (c.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+impl From<Color> for Oklab {
+    fn from(Color(r, g, b): Color) -> Self {
+        let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+
+        let l = cbrtf(0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b);
+        let m = cbrtf(0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b);
+        let s = cbrtf(0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b);
+
+        Oklab {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+}
+
+impl From<Oklab> for Color {
+    fn from(Oklab { l, a, b }: Oklab) -> Self {
+        let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+        let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+        let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+        let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+        Color(
+            from_linear(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+            from_linear(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+            from_linear(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+        )
+    }
+}
+
+/// Colors at positions from 0.0 to 1.0, blended in between
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gradient<const S: usize> {
+    /// Position and color, in order of position
+    stops: [(f32, Color); S],
+}
+
+impl<const S: usize> Gradient<S> {
+    pub const fn new(stops: [(f32, Color); S]) -> Self {
+        Self { stops }
+    }
+
+    /// Color at `t`, before the first stop and after the last the color holds
+    pub fn sample(&self, t: f32) -> Color {
+        let Some(&(first_at, first)) = self.stops.first() else {
+            return Color::BLACK;
+        };
+        if t <= first_at {
+            return first;
+        }
+
+        for pair in self.stops.windows(2) {
+            let [(from_at, from), (to_at, to)] = [pair[0], pair[1]];
+            if t <= to_at {
+                return from.lerp(to, (t - from_at) / (to_at - from_at).max(f32::EPSILON));
+            }
+        }
+        self.stops[S - 1].1
+    }
+}
+
+/// Most colors a palette holds
+pub const MAX_PALETTE_COLORS: usize = 10;
+
+/// A set of colors animations pick from, by index or as an even gradient
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    colors: [Color; MAX_PALETTE_COLORS],
+    len: usize,
+}
+
+impl Palette {
+    /// Colors of the 2024 Formula 1 teams, in order of the constructors' championship
+    pub const TEAMS_2024: Palette = Palette::new(&[
+        Color(255, 135, 0),   // McLaren
+        Color(220, 0, 0),     // Ferrari
+        Color(30, 65, 255),   // Red Bull
+        Color(0, 210, 190),   // Mercedes
+        Color(0, 110, 120),   // Aston Martin
+        Color(2, 144, 240),   // Alpine
+        Color(160, 207, 205), // Haas
+        Color(60, 130, 200),  // VCARB
+        Color(0, 82, 255),    // Williams
+        Color(0, 160, 0),     // Sauber
+    ]);
+    /// Red, white and blue, from top to bottom
+    pub const DUTCH_FLAG: Palette = Palette::new(&[Color::RED, Color::WHITE, Color::BLUE]);
+    /// Oranges and reds of a Kingsday crowd
+    pub const KINGSDAY: Palette = Palette::new(&[Color::ORANGE, Color(255, 105, 0), Color::RED]);
+    /// Reddish highlight, warm orange and deep red
+    pub const SUNSET: Palette = Palette::new(&[Color(255, 30, 0), Color(255, 100, 0), Color::RED]);
+    /// The chasing car, the chased car, the overtake flash and the wake on the rest of the track
+    pub const OVERTAKE: Palette = Palette::new(&[
+        Color::RED,
+        Color(0, 0, 250),
+        Color(255, 255, 200),
+        Color(20, 2, 0),
+    ]);
+    pub const RAINBOW: Palette = Palette::new(&[
+        Color::RED,
+        Color(255, 127, 0),
+        Color(255, 255, 0),
+        Color::GREEN,
+        Color::BLUE,
+        Color(75, 0, 130),
+        Color(148, 0, 211),
+    ]);
+
+    /// Colors past `MAX_PALETTE_COLORS` are left out
+    pub const fn new(colors: &[Color]) -> Self {
+        let mut palette = Self {
+            colors: [Color::BLACK; MAX_PALETTE_COLORS],
+            len: 0,
+        };
+        while palette.len < colors.len() && palette.len < MAX_PALETTE_COLORS {
+            palette.colors[palette.len] = colors[palette.len];
+            palette.len += 1;
+        }
+        palette
+    }
+
+    pub fn colors(&self) -> &[Color] {
+        &self.colors[..self.len]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Color by index, wrapping around. Black for an empty palette.
+    pub fn color(&self, index: usize) -> Color {
+        match self.len {
+            0 => Color::BLACK,
+            len => self.colors[index % len],
+        }
+    }
+
+    /// The colors as an even gradient, `t` from 0.0 (first color) to 1.0 (last color)
+    pub fn sample(&self, t: f32) -> Color {
+        if self.len < 2 {
+            return self.color(0);
+        }
+
+        let position = t.clamp(0.0, 1.0) * (self.len - 1) as f32;
+        let index = (position as usize).min(self.len - 2);
+        self.colors[index].lerp(self.colors[index + 1], position - index as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Color::from_hsv(0.0, 1.0, 1.0), Color::RED);
+        assert_eq!(Color::from_hsv(1.0 / 3.0, 1.0, 1.0), Color::GREEN);
+        assert_eq!(Color::from_hsl(2.0 / 3.0, 1.0, 0.5), Color::BLUE);
+        assert_eq!(Color::from_hsl(0.0, 0.0, 1.0), Color::WHITE);
+
+        for color in [
+            Color::ORANGE,
+            Color(12, 200, 99),
+            Color::WHITE,
+            Color::BLACK,
+        ] {
+            assert_eq!(Color::from(Oklab::from(color)), color);
+        }
+
+        // Halfway between red and green is a lot brighter in OKLab than the muddy RGB blend
+        let rgb = Color::RED.lerp(Color::GREEN, 0.5);
+        let oklab = Color::RED.lerp_oklab(Color::GREEN, 0.5);
+        assert_eq!(rgb, Color(128, 128, 0));
+        assert!(Oklab::from(oklab).l > Oklab::from(rgb).l);
+    }
+
+    #[test]
+    fn gradients_and_palettes() {
+        let gradient = Gradient::new([(0.2, Color::BLACK), (0.6, Color(200, 100, 0))]);
+        assert_eq!(gradient.sample(0.0), Color::BLACK);
+        assert_eq!(gradient.sample(0.4), Color(100, 50, 0));
+        assert_eq!(gradient.sample(1.0), Color(200, 100, 0));
+
+        let palette = Palette::DUTCH_FLAG;
+        assert_eq!(palette.len(), 3);
+        assert_eq!(palette.color(4), Color::WHITE);
+        assert_eq!(palette.sample(0.5), Color::WHITE);
+        assert_eq!(palette.sample(0.75), Color(128, 128, 255));
+        assert_eq!(Palette::new(&[]).color(1), Color::BLACK);
+    }
+}
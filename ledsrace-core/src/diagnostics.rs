@@ -0,0 +1,158 @@
+use core::fmt;
+
+use embassy_time::Duration;
+
+use crate::{power::PowerModel, Circuit, Color, Priority, Sector};
+
+/// Sweep color, dim so that the low gains show without limiting. The higher gains are limited to
+/// the supply budget, see `DiagnosticStep::power_model`
+const SWEEP: Color = Color(32, 32, 32);
+
+/// Brightness steps of the sweep, one for every HD108 gain level
+const BRIGHTNESS_STEPS: u8 = 32;
+
+/// A single step of the assembly test pattern
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiagnosticStep {
+    /// All LEDs in one color, to check every channel of every LED
+    Fill(Color),
+    /// A single LED lit, walking the track in logical order to check the chain order
+    Chase(usize),
+    /// Every sector in its own color
+    Sectors,
+    /// All LEDs dim white, with the driver set to this brightness
+    Brightness(u8),
+}
+
+/// Steps of the test pattern, in the order they should be shown
+pub fn diagnostic_steps(led_count: usize) -> impl Iterator<Item = DiagnosticStep> {
+    [Color::RED, Color::GREEN, Color::BLUE, Color::WHITE]
+        .into_iter()
+        .map(DiagnosticStep::Fill)
+        .chain((0..led_count).map(DiagnosticStep::Chase))
+        .chain(core::iter::once(DiagnosticStep::Sectors))
+        .chain((0..BRIGHTNESS_STEPS).map(|gain| DiagnosticStep::Brightness(gain << 3)))
+}
+
+impl DiagnosticStep {
+    /// Render the step into the LED buffer of the circuit
+    pub fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        circuit.led_buffer().clear();
+
+        match *self {
+            DiagnosticStep::Fill(color) => {
+                for i in 0..circuit.led_count() {
+                    circuit.set_led(i, color, Priority::Normal);
+                }
+            }
+            DiagnosticStep::Chase(index) => circuit.set_led(index, Color::WHITE, Priority::Normal),
+            DiagnosticStep::Sectors => {
+                for (sector, color) in [
+                    (Sector::_1, Color::RED),
+                    (Sector::_2, Color::GREEN),
+                    (Sector::_3, Color::BLUE),
+                ] {
+                    for i in circuit.sector_indices(sector) {
+                        circuit.set_led(i, color, Priority::Normal);
+                    }
+                }
+            }
+            DiagnosticStep::Brightness(_) => {
+                for i in 0..circuit.led_count() {
+                    circuit.set_led(i, SWEEP, Priority::Normal);
+                }
+            }
+        }
+    }
+
+    /// Driver brightness for this step, the lowest brightness unless sweeping
+    pub fn brightness(&self) -> u8 {
+        match *self {
+            DiagnosticStep::Brightness(brightness) => brightness,
+            _ => 0,
+        }
+    }
+
+    /// Current estimate of the step at its driver brightness, to limit it to the supply budget
+    pub fn power_model(&self) -> PowerModel {
+        PowerModel::hd108(self.brightness() >> 3)
+    }
+
+    /// How long the step should be shown
+    pub fn hold(&self) -> Duration {
+        match self {
+            DiagnosticStep::Fill(_) | DiagnosticStep::Sectors => Duration::from_secs(2),
+            DiagnosticStep::Chase(_) => Duration::from_millis(100),
+            DiagnosticStep::Brightness(_) => Duration::from_millis(150),
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticStep::Fill(Color::RED) => write!(f, "All LEDs red"),
+            DiagnosticStep::Fill(Color::GREEN) => write!(f, "All LEDs green"),
+            DiagnosticStep::Fill(Color::BLUE) => write!(f, "All LEDs blue"),
+            DiagnosticStep::Fill(Color::WHITE) => write!(f, "All LEDs white"),
+            DiagnosticStep::Fill(Color(r, g, b)) => write!(f, "All LEDs ({}, {}, {})", r, g, b),
+            DiagnosticStep::Chase(index) => write!(f, "LED {}", index),
+            DiagnosticStep::Sectors => write!(f, "Sectors 1 red, 2 green, 3 blue"),
+            DiagnosticStep::Brightness(brightness) => write!(f, "Brightness {}", brightness),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{power::PowerLimiter, zandvoort::Zandvoort};
+
+    #[test]
+    fn steps_cover_every_led() {
+        let steps = diagnostic_steps(216);
+        let chased: usize = steps
+            .filter(|step| matches!(step, DiagnosticStep::Chase(_)))
+            .count();
+        assert_eq!(chased, 216);
+
+        let last = diagnostic_steps(216).last().unwrap();
+        assert_eq!(last, DiagnosticStep::Brightness(248));
+    }
+
+    #[test]
+    fn render_chase_and_sectors() {
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+
+        DiagnosticStep::Chase(5).render(&mut zandvoort);
+        let colors = zandvoort.led_buffer().get_colors();
+        assert_eq!(colors[5].0, Color::WHITE);
+        assert_eq!(
+            colors.iter().filter(|(c, _)| *c != Color(0, 0, 0)).count(),
+            1
+        );
+
+        DiagnosticStep::Sectors.render(&mut zandvoort);
+        let colors = zandvoort.led_buffer().get_colors();
+        assert_eq!(colors[0].0, Color::RED);
+        assert_eq!(colors[100].0, Color::GREEN);
+        assert_eq!(colors[215].0, Color::BLUE);
+    }
+
+    #[test]
+    fn limited_steps_stay_within_usb_budget() {
+        const BUDGET_MA: f32 = 400.0;
+        let mut zandvoort: Zandvoort<216> = Zandvoort::new();
+        let mut limiter = PowerLimiter::new(PowerModel::HD108_DEFAULT_GAIN, BUDGET_MA);
+        limiter.set_release(1.0);
+
+        for step in diagnostic_steps(216) {
+            let model = step.power_model();
+            step.render(&mut zandvoort);
+            limiter.set_model(model);
+            limiter.apply(zandvoort.led_buffer());
+            let colors = zandvoort.led_buffer().get_colors().iter().map(|(c, _)| *c);
+            assert!(model.frame_ma(colors) <= BUDGET_MA, "{step}");
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{Color, LedStateBuffer};
+
+/// Output stage that quantises frames to 8 bits per channel while keeping the rounding error of
+/// every LED for the next frame, so brightness below one step is averaged over time instead of
+/// being truncated. Fades towards black stay smooth rather than stepping and flickering.
+pub struct TemporalDither<const N: usize> {
+    /// Quantisation error per LED and channel carried into the next frame, in 1/256 steps
+    residuals: [[i16; 3]; N],
+    enabled: bool,
+}
+
+impl<const N: usize> TemporalDither<N> {
+    pub const fn new(enabled: bool) -> Self {
+        Self {
+            residuals: [[0; 3]; N],
+            enabled,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.clear();
+        }
+        self.enabled = enabled;
+    }
+
+    /// Forget the carried error, e.g. when switching to an unrelated animation
+    pub fn clear(&mut self) {
+        self.residuals = [[0; 3]; N];
+    }
+
+    /// Quantise an 8.8 fixed point color (256 is one 8-bit step) for the LED at `index`
+    pub fn quantize(&mut self, index: usize, target: [u16; 3]) -> Color {
+        if index >= N {
+            return Color(0, 0, 0);
+        }
+
+        if !self.enabled {
+            return Color(
+                (target[0] >> 8) as u8,
+                (target[1] >> 8) as u8,
+                (target[2] >> 8) as u8,
+            );
+        }
+
+        let mut out = [0u8; 3];
+        for (channel, value) in out.iter_mut().enumerate() {
+            let residual = &mut self.residuals[index][channel];
+            let wanted = target[channel] as i32 + *residual as i32;
+            let quantized = (wanted >> 8).clamp(0, 255);
+            *residual = (wanted - (quantized << 8)) as i16;
+            *value = quantized as u8;
+        }
+
+        Color(out[0], out[1], out[2])
+    }
+
+    /// Scale the frame in `buffer` by `brightness` (0.0 to 1.0) and quantise it in place. The
+    /// fractions animations wrote with `set_led_fine` are kept until here, so their fades are
+    /// dithered even when nothing is scaled.
+    pub fn apply(&mut self, buffer: &mut LedStateBuffer<N>, brightness: f32) {
+        let brightness = (brightness.clamp(0.0, 1.0) * 256.0) as u32;
+        for index in 0..N {
+            let target = buffer
+                .fine_color(index)
+                .map(|channel| ((channel as u32 * brightness) >> 8) as u16);
+            let Color(r, g, b) = self.quantize(index, target);
+            buffer.set_fine_color(index, [(r as u16) << 8, (g as u16) << 8, (b as u16) << 8]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Priority;
+
+    const FRAMES: u32 = 1000;
+
+    fn average_output(dither: &mut TemporalDither<1>, target: [u16; 3]) -> [f32; 3] {
+        let mut sum = [0u32; 3];
+        for _ in 0..FRAMES {
+            let color = dither.quantize(0, target);
+            sum[0] += color.0 as u32;
+            sum[1] += color.1 as u32;
+            sum[2] += color.2 as u32;
+        }
+        sum.map(|s| s as f32 / FRAMES as f32)
+    }
+
+    #[test]
+    fn average_matches_sub_step_target() {
+        let mut dither = TemporalDither::<1>::new(true);
+        // 0.25, 10.5 and 254.75 steps
+        let average = average_output(&mut dither, [64, 10 * 256 + 128, 254 * 256 + 192]);
+        assert!((average[0] - 0.25).abs() < 0.01);
+        assert!((average[1] - 10.5).abs() < 0.01);
+        assert!((average[2] - 254.75).abs() < 0.01);
+    }
+
+    #[test]
+    fn disabled_truncates() {
+        let mut dither = TemporalDither::<1>::new(false);
+        let average = average_output(&mut dither, [64, 10 * 256 + 128, 255 * 256]);
+        assert_eq!(average, [0.0, 10.0, 255.0]);
+    }
+
+    #[test]
+    fn fine_fade_dithers_at_full_brightness() {
+        let mut dither = TemporalDither::<1>::new(true);
+        let mut buffer = LedStateBuffer::<1>::new();
+
+        let mut sum = 0;
+        for _ in 0..FRAMES {
+            // 2.25 steps, which an 8-bit color would truncate to 2
+            buffer.set_led_fine(0, Color(9, 0, 0).scale_fine(0.25), Priority::Normal);
+            dither.apply(&mut buffer, 1.0);
+            sum += buffer.get_colors()[0].0 .0 as u32;
+        }
+        assert!((sum as f32 / FRAMES as f32 - 2.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn set_color_keeps_the_fractions_of_other_leds() {
+        let mut buffer = LedStateBuffer::<2>::new();
+        let fine = Color(9, 0, 0).scale_fine(0.25);
+        buffer.set_led_fine(0, fine, Priority::Normal);
+        buffer.set_led_fine(1, fine, Priority::Normal);
+
+        buffer.set_color(1, Color(4, 0, 0), Priority::Normal);
+        assert_eq!(buffer.fine_color(0), fine);
+        assert_eq!(buffer.fine_color(1), [4 << 8, 0, 0]);
+    }
+
+    #[test]
+    fn scaled_frame_averages_to_target() {
+        let mut dither = TemporalDither::<2>::new(true);
+        let mut buffer = LedStateBuffer::<2>::new();
+
+        let mut sum = [0u32; 2];
+        for _ in 0..FRAMES {
+            buffer.set_led(0, Color(3, 0, 0), Priority::Normal);
+            buffer.set_led(1, Color(200, 0, 0), Priority::Normal);
+            dither.apply(&mut buffer, 0.1);
+            sum[0] += buffer.get_colors()[0].0 .0 as u32;
+            sum[1] += buffer.get_colors()[1].0 .0 as u32;
+        }
+
+        // 0.1 brightness is 25/256 in fixed point, so the targets are 0.29 and 19.53 steps
+        let expected = [3.0 * 25.0 / 256.0, 200.0 * 25.0 / 256.0];
+        assert!((sum[0] as f32 / FRAMES as f32 - expected[0]).abs() < 0.01);
+        assert!((sum[1] as f32 / FRAMES as f32 - expected[1]).abs() < 0.01);
+    }
+}
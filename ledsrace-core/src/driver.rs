@@ -0,0 +1,26 @@
+use crate::Color;
+
+pub mod apa102;
+pub mod hd108;
+#[cfg(test)]
+mod mock;
+pub mod ws2812;
+
+pub use apa102::APA102;
+pub use hd108::HD108;
+pub use ws2812::WS2812;
+
+/// Output device for a chain of LEDs
+#[allow(async_fn_in_trait)]
+pub trait LedDriver {
+    type Error;
+
+    /// Write a full frame, one color per LED in chain order. LEDs without a color are turned off.
+    async fn write(&mut self, colors: impl IntoIterator<Item = Color>) -> Result<(), Self::Error>;
+
+    /// Turn all LEDs off
+    async fn set_off(&mut self) -> Result<(), Self::Error>;
+
+    /// Set the global brightness used for the following frames, 255 is the maximum the device supports
+    fn set_brightness(&mut self, brightness: u8);
+}
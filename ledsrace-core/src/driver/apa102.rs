@@ -0,0 +1,129 @@
+use embedded_hal_async::spi::SpiBus;
+
+use super::LedDriver;
+use crate::Color;
+
+/// 32 bits of zeros for the start frame
+const START_FRAME_BYTES: usize = 4;
+
+pub const fn required_buffer_size<const N: usize>() -> usize {
+    // 4 bytes per LED, then half a clock pulse per LED to push the data through the chain
+    START_FRAME_BYTES + (N * 4) + N.div_ceil(16)
+}
+
+/// APA102 (and SK9822) LEDs with a 5 bit global brightness per LED
+pub struct APA102<SPI, const N: usize> {
+    spi: SPI,
+    buf: &'static mut [u8],
+    /// 5 bit global brightness
+    brightness: u8,
+}
+
+impl<SPI, const N: usize> APA102<SPI, N>
+where
+    SPI: SpiBus<u8>,
+{
+    pub fn new(spi: SPI, buf: &'static mut [u8]) -> Self {
+        if buf.len() != required_buffer_size::<N>() {
+            panic!("Buffer size is not correct");
+        }
+        Self {
+            spi,
+            buf,
+            brightness: 0x1F,
+        }
+    }
+
+    fn create_led_frame(brightness: u8, color: Color) -> [u8; 4] {
+        // Three 1 bits mark the start of an LED frame, the LEDs expect blue, green, red
+        [0b1110_0000 | brightness, color.2, color.1, color.0]
+    }
+}
+
+impl<SPI, const N: usize> LedDriver for APA102<SPI, N>
+where
+    SPI: SpiBus<u8>,
+{
+    type Error = SPI::Error;
+
+    async fn write(&mut self, colors: impl IntoIterator<Item = Color>) -> Result<(), SPI::Error> {
+        // Zeros for the start and end frame
+        self.buf.fill(0);
+
+        let mut colors = colors.into_iter();
+        for i in 0..N {
+            let color = colors.next().unwrap_or(Color(0, 0, 0));
+            let index = START_FRAME_BYTES + i * 4;
+            self.buf[index..index + 4]
+                .copy_from_slice(&Self::create_led_frame(self.brightness, color));
+        }
+
+        self.spi.write(self.buf).await?;
+
+        Ok(())
+    }
+
+    async fn set_off(&mut self) -> Result<(), SPI::Error> {
+        self.write([]).await
+    }
+
+    fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness >> 3;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::driver::mock::{static_buffer, RecordingBus};
+
+    #[test]
+    fn encode_frame() {
+        let mut apa102: APA102<_, 2> = APA102::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<2>()),
+        );
+        block_on(apa102.write([Color(0x12, 0x34, 0x56)])).unwrap();
+
+        let written = apa102.spi.last_write();
+        assert_eq!(written.len(), 4 + 2 * 4 + 1);
+        // Start frame
+        assert_eq!(written[..4], [0x00, 0x00, 0x00, 0x00]);
+        // LED 0 in blue, green, red order
+        assert_eq!(written[4..8], [0xFF, 0x56, 0x34, 0x12]);
+        // LEDs past the end of the colors are turned off
+        assert_eq!(written[8..12], [0xFF, 0x00, 0x00, 0x00]);
+        // End frame
+        assert_eq!(written[12..], [0x00]);
+    }
+
+    #[test]
+    fn brightness_is_per_led_field() {
+        let mut apa102: APA102<_, 1> = APA102::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<1>()),
+        );
+        apa102.set_brightness(0x40);
+        block_on(apa102.write([Color(1, 2, 3)])).unwrap();
+
+        assert_eq!(apa102.spi.last_write()[4..8], [0xE8, 3, 2, 1]);
+    }
+
+    #[test]
+    fn end_frame_covers_chain() {
+        assert_eq!(required_buffer_size::<16>(), 4 + 64 + 1);
+        assert_eq!(required_buffer_size::<216>(), 4 + 864 + 14);
+
+        let mut apa102: APA102<_, 216> = APA102::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<216>()),
+        );
+        block_on(apa102.set_off()).unwrap();
+
+        let written = apa102.spi.last_write();
+        assert!(written[4..868].chunks(4).all(|led| led == [0xFF, 0, 0, 0]));
+        assert!(written[868..].iter().all(|&b| b == 0));
+    }
+}
@@ -0,0 +1,204 @@
+use embedded_hal_async::spi::SpiBus;
+
+use super::LedDriver;
+use crate::Color;
+
+/// At least 128 bits of zeros for the start frame
+const PREAMBLE_ZERO_BYTES: usize = 16;
+
+pub const fn required_buffer_size<const N: usize>() -> usize {
+    // We need 8 bytes per LED and then 1 bit per LED
+    PREAMBLE_ZERO_BYTES + (N * 8) + (N / 8)
+}
+
+pub struct HD108<SPI, const N: usize> {
+    spi: SPI,
+    buf: &'static mut [u8],
+    /// 5 bit current gain applied to all channels
+    gain: u8,
+}
+
+impl<SPI, const N: usize> HD108<SPI, N>
+where
+    SPI: SpiBus<u8>,
+{
+    pub fn new(spi: SPI, buf: &'static mut [u8]) -> Self {
+        if buf.len() != required_buffer_size::<N>() {
+            panic!("Buffer size is not correct");
+        }
+        encode_frame::<N>(buf, 0, []);
+        Self { spi, buf, gain: 0 }
+    }
+
+    /// Encode the colors of the next frame without sending it, LEDs without a color are turned
+    /// off. See `flush`.
+    pub fn encode(&mut self, colors: impl IntoIterator<Item = Color>) {
+        encode_frame::<N>(self.buf, self.gain, colors);
+    }
+
+    /// Send the last encoded frame. The SPI DMA driver copies the frame before sending it, so
+    /// the next frame can be rendered while this runs (e.g. using `join`) and encoded once it is
+    /// done.
+    pub async fn flush(&mut self) -> Result<(), SPI::Error> {
+        self.spi.write(self.buf).await
+    }
+}
+
+fn encode_frame<const N: usize>(buf: &mut [u8], gain: u8, colors: impl IntoIterator<Item = Color>) {
+    buf.fill(0);
+
+    let mut colors = colors.into_iter();
+    for i in 0..N {
+        let led_frame = if let Some(Color(red, green, blue)) = colors.next() {
+            // Convert the 8-bit RGB values to 16-bit values
+            let red = ((red as u16) << 8) | (red as u16);
+            let green = ((green as u16) << 8) | (green as u16);
+            let blue = ((blue as u16) << 8) | (blue as u16);
+
+            create_led_frame(gain, red, green, blue)
+        } else {
+            // LED off
+            create_led_frame(gain, 0x0000, 0x0000, 0x0000)
+        };
+        let index = PREAMBLE_ZERO_BYTES + i * 8;
+        buf[index..index + 8].copy_from_slice(&led_frame);
+    }
+
+    // After the last frame we required additional clock pulses equal to the number of LEDs in the strip.
+    // Since we already zeroed the buffer we don't actually have to insert them here. Just supply the full buffer to the SPI bus.
+}
+
+// Function to create an LED frame
+fn create_led_frame(gain: u8, red: u16, green: u16, blue: u16) -> [u8; 8] {
+    let start_code: u8 = 0b1;
+    // Gain 0 is regulation level 2 - 2.24 mA
+    let red_gain: u8 = gain;
+    let green_gain: u8 = gain;
+    let blue_gain: u8 = gain;
+
+    // Combine the gain values into a 15-bit number
+    let current_gain = ((red_gain as u16) << 10) | ((green_gain as u16) << 5) | (blue_gain as u16);
+
+    // The first byte contains the start code and the 7 most significant bits of the current gain
+    let first_byte = (start_code << 7) | ((current_gain >> 8) as u8 & 0x7F);
+
+    // The second byte contains the remaining 8 bits of the current gain
+    let second_byte = (current_gain & 0xFF) as u8;
+
+    [
+        first_byte,           // Start code and part of current gain
+        second_byte,          // Remaining current gain bits
+        (red >> 8) as u8,     // High byte of red
+        (red & 0xFF) as u8,   // Low byte of red
+        (green >> 8) as u8,   // High byte of green
+        (green & 0xFF) as u8, // Low byte of green
+        (blue >> 8) as u8,    // High byte of blue
+        (blue & 0xFF) as u8,  // Low byte of blue
+    ]
+}
+
+impl<SPI, const N: usize> LedDriver for HD108<SPI, N>
+where
+    SPI: SpiBus<u8>,
+{
+    type Error = SPI::Error;
+
+    async fn set_off(&mut self) -> Result<(), SPI::Error> {
+        // Set all LEDs to off, a later `flush` sends the off frame again
+        self.buf.fill(0);
+
+        // Write the data to the SPI bus
+        self.spi.write(self.buf).await?;
+
+        Ok(())
+    }
+
+    async fn write(&mut self, colors: impl IntoIterator<Item = Color>) -> Result<(), SPI::Error> {
+        self.encode(colors);
+        self.flush().await
+    }
+
+    /// HD108 has no global brightness, this sets the 5 bit current gain of every channel instead.
+    /// The LEDs stay lit at brightness 0, they are just driven with the lowest current.
+    fn set_brightness(&mut self, brightness: u8) {
+        self.gain = brightness >> 3;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::driver::mock::{static_buffer, RecordingBus};
+
+    #[test]
+    fn encode_colors() {
+        let mut hd108: HD108<_, 2> = HD108::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<2>()),
+        );
+        block_on(hd108.write([Color(0x12, 0x34, 0x56)])).unwrap();
+
+        let written = hd108.spi.last_write();
+        assert_eq!(written.len(), 16 + 2 * 8);
+        assert_eq!(written[..16], [0; 16]);
+        assert_eq!(
+            written[16..24],
+            [0x80, 0x00, 0x12, 0x12, 0x34, 0x34, 0x56, 0x56]
+        );
+        // LEDs past the end of the colors are turned off
+        assert_eq!(written[24..32], [0x80, 0x00, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn brightness_sets_gain() {
+        let mut hd108: HD108<_, 1> = HD108::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<1>()),
+        );
+        hd108.set_brightness(255);
+        block_on(hd108.write([Color(0xFF, 0, 0)])).unwrap();
+
+        // Start bit followed by three 5 bit gains of 0b11111
+        assert_eq!(
+            hd108.spi.last_write()[16..24],
+            [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn set_off_clears_buffer() {
+        let mut hd108: HD108<_, 8> = HD108::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<8>()),
+        );
+        block_on(hd108.set_off()).unwrap();
+
+        let written = hd108.spi.last_write();
+        assert_eq!(written.len(), 16 + 8 * 8 + 1);
+        assert!(written.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn flush_sends_the_encoded_frame() {
+        let mut hd108: HD108<_, 1> = HD108::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<1>()),
+        );
+
+        // Nothing encoded yet is an all off frame
+        block_on(hd108.flush()).unwrap();
+        hd108.encode([Color(1, 0, 0)]);
+        block_on(hd108.flush()).unwrap();
+        block_on(hd108.set_off()).unwrap();
+        block_on(hd108.flush()).unwrap();
+
+        let writes = &hd108.spi.writes;
+        assert_eq!(writes.len(), 4);
+        assert_eq!(writes[0][16..24], [0x80, 0x00, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(writes[1][16..24], [0x80, 0x00, 1, 1, 0, 0, 0, 0]);
+        // The LEDs stay off after `set_off`
+        assert!(writes[3].iter().all(|&b| b == 0));
+    }
+}
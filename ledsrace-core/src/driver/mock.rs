@@ -0,0 +1,55 @@
+extern crate std;
+
+use core::convert::Infallible;
+use std::{boxed::Box, vec, vec::Vec};
+
+use embedded_hal_async::spi::{ErrorType, SpiBus};
+
+/// SPI bus that records every write so tests can check the encoded bytes
+#[derive(Default)]
+pub struct RecordingBus {
+    pub writes: Vec<Vec<u8>>,
+}
+
+impl RecordingBus {
+    pub fn last_write(&self) -> &[u8] {
+        self.writes.last().expect("nothing was written")
+    }
+}
+
+impl ErrorType for RecordingBus {
+    type Error = Infallible;
+}
+
+impl SpiBus<u8> for RecordingBus {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        words.fill(0);
+        Ok(())
+    }
+
+    async fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.writes.push(words.to_vec());
+        Ok(())
+    }
+
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        self.writes.push(write.to_vec());
+        read.fill(0);
+        Ok(())
+    }
+
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        self.writes.push(words.to_vec());
+        words.fill(0);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Drivers keep their frame buffer for the lifetime of the program
+pub fn static_buffer(size: usize) -> &'static mut [u8] {
+    Box::leak(vec![0xAA; size].into_boxed_slice())
+}
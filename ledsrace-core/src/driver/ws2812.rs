@@ -0,0 +1,137 @@
+use embedded_hal_async::spi::SpiBus;
+
+use super::LedDriver;
+use crate::Color;
+
+/// Every data bit is sent as 4 SPI bits, so one data byte takes 4 SPI bytes
+const BYTES_PER_LED: usize = 3 * 4;
+/// Low time after the frame that latches the colors, more than 280 µs at 3.2 MHz
+const RESET_BYTES: usize = 112;
+
+/// SPI byte for each pair of data bits, a 1 is sent as 1110 and a 0 as 1000
+const BIT_PATTERNS: [u8; 4] = [0b1000_1000, 0b1000_1110, 0b1110_1000, 0b1110_1110];
+
+pub const fn required_buffer_size<const N: usize>() -> usize {
+    N * BYTES_PER_LED + RESET_BYTES
+}
+
+/// WS2812 (and SK6812 RGB) LEDs driven from the MOSI line of an SPI bus.
+///
+/// The bus has to be clocked at 3.2 MHz, so that 4 SPI bits match the 1.25 µs bit time of the LEDs.
+/// The LEDs have no brightness control of their own, so brightness is applied to the colors.
+pub struct WS2812<SPI, const N: usize> {
+    spi: SPI,
+    buf: &'static mut [u8],
+    brightness: u8,
+}
+
+impl<SPI, const N: usize> WS2812<SPI, N>
+where
+    SPI: SpiBus<u8>,
+{
+    pub fn new(spi: SPI, buf: &'static mut [u8]) -> Self {
+        if buf.len() != required_buffer_size::<N>() {
+            panic!("Buffer size is not correct");
+        }
+        Self {
+            spi,
+            buf,
+            brightness: 255,
+        }
+    }
+
+    fn encode_byte(byte: u8) -> [u8; 4] {
+        [
+            BIT_PATTERNS[(byte >> 6) as usize & 0b11],
+            BIT_PATTERNS[(byte >> 4) as usize & 0b11],
+            BIT_PATTERNS[(byte >> 2) as usize & 0b11],
+            BIT_PATTERNS[byte as usize & 0b11],
+        ]
+    }
+
+    fn scale(&self, value: u8) -> u8 {
+        ((value as u16 * (self.brightness as u16 + 1)) >> 8) as u8
+    }
+}
+
+impl<SPI, const N: usize> LedDriver for WS2812<SPI, N>
+where
+    SPI: SpiBus<u8>,
+{
+    type Error = SPI::Error;
+
+    async fn write(&mut self, colors: impl IntoIterator<Item = Color>) -> Result<(), SPI::Error> {
+        // Zeros for the reset time
+        self.buf.fill(0);
+
+        let mut colors = colors.into_iter();
+        for i in 0..N {
+            let color = colors.next().unwrap_or(Color(0, 0, 0));
+            let index = i * BYTES_PER_LED;
+            // The LEDs expect green, red, blue
+            for (j, value) in [color.1, color.0, color.2].into_iter().enumerate() {
+                let encoded = Self::encode_byte(self.scale(value));
+                self.buf[index + j * 4..index + j * 4 + 4].copy_from_slice(&encoded);
+            }
+        }
+
+        self.spi.write(self.buf).await?;
+
+        Ok(())
+    }
+
+    async fn set_off(&mut self) -> Result<(), SPI::Error> {
+        self.write([]).await
+    }
+
+    fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use super::*;
+    use crate::driver::mock::{static_buffer, RecordingBus};
+
+    #[test]
+    fn encode_frame() {
+        let mut ws2812: WS2812<_, 2> = WS2812::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<2>()),
+        );
+        block_on(ws2812.write([Color(0xFF, 0x00, 0b1001_0110)])).unwrap();
+
+        let written = ws2812.spi.last_write();
+        assert_eq!(written.len(), 2 * 12 + 112);
+        assert_eq!(
+            written[..12],
+            [
+                0x88, 0x88, 0x88, 0x88, // Green 0x00
+                0xEE, 0xEE, 0xEE, 0xEE, // Red 0xFF
+                0xE8, 0x8E, 0x8E, 0xE8, // Blue 10 01 01 10
+            ]
+        );
+        // LED 1 is off and every bit of it still needs a high pulse
+        assert!(written[12..24].iter().all(|&b| b == 0x88));
+        assert!(written[24..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn brightness_scales_colors() {
+        let mut ws2812: WS2812<_, 1> = WS2812::new(
+            RecordingBus::default(),
+            static_buffer(required_buffer_size::<1>()),
+        );
+        ws2812.set_brightness(127);
+        block_on(ws2812.write([Color(0xFF, 0x80, 0x00)])).unwrap();
+
+        // 0xFF becomes 0x7F and 0x80 becomes 0x40
+        assert_eq!(
+            ws2812.spi.last_write()[..8],
+            [0x8E, 0x88, 0x88, 0x88, 0x8E, 0xEE, 0xEE, 0xEE]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Fixed-point math for the per LED work in animations.
+//!
+//! The ESP32-C3 has no FPU, so every `f32` operation is a call into soft-float and `libm::sinf`
+//! costs hundreds of them. Numbers here are Q16: `i32` with 16 fractional bits. Angles are `u16`
+//! turns, 65536 is a full circle, so they wrap around for free.
+
+use libm::floorf;
+
+/// Number with 16 fractional bits, `ONE` is 1.0
+pub type Q16 = i32;
+
+pub const ONE: Q16 = 1 << 16;
+
+/// Segments in a quarter of a sine wave
+const SIN_STEPS: usize = 256;
+
+/// First quarter of a sine wave, in Q16, with the end point so lookups can interpolate
+static SIN_TABLE: [Q16; SIN_STEPS + 1] = sin_table();
+
+const fn sin_table() -> [Q16; SIN_STEPS + 1] {
+    let mut table = [0; SIN_STEPS + 1];
+    let mut i = 0;
+    while i <= SIN_STEPS {
+        let x = i as f64 / SIN_STEPS as f64 * core::f64::consts::FRAC_PI_2;
+        // Taylor series, accurate well below one Q16 step up to π/2
+        let mut term = x;
+        let mut sum = x;
+        let mut n = 1;
+        while n < 10 {
+            term = -term * x * x / ((2 * n) * (2 * n + 1)) as f64;
+            sum += term;
+            n += 1;
+        }
+        table[i] = (sum * ONE as f64 + 0.5) as Q16;
+        i += 1;
+    }
+    table
+}
+
+pub fn from_f32(value: f32) -> Q16 {
+    (value * ONE as f32) as Q16
+}
+
+pub fn to_f32(value: Q16) -> f32 {
+    value as f32 / ONE as f32
+}
+
+/// Angle of a number of turns, 1.0 is a full circle
+pub fn turns(value: f32) -> u16 {
+    // Drop the whole turns first, past 32768 of them the cast to `i32` would saturate
+    let fraction = value - floorf(value);
+    (fraction * 65536.0) as u32 as u16
+}
+
+pub fn mul(a: Q16, b: Q16) -> Q16 {
+    ((a as i64 * b as i64) >> 16) as Q16
+}
+
+/// Sine of an angle in turns, from -`ONE` to `ONE`
+pub fn sin(angle: u16) -> Q16 {
+    const QUARTER: u16 = 1 << 14;
+    const FRAC_BITS: u32 = 14 - SIN_STEPS.trailing_zeros();
+
+    let quadrant = angle / QUARTER;
+    let mut x = angle % QUARTER;
+    // The second and fourth quarter run the table backwards
+    if quadrant % 2 == 1 {
+        x = QUARTER - x;
+    }
+
+    let index = (x >> FRAC_BITS) as usize;
+    let frac = (x & ((1 << FRAC_BITS) - 1)) as Q16;
+    let value = if frac == 0 {
+        SIN_TABLE[index]
+    } else {
+        let (a, b) = (SIN_TABLE[index], SIN_TABLE[index + 1]);
+        a + (((b - a) * frac) >> FRAC_BITS)
+    };
+
+    if quadrant >= 2 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Cosine of an angle in turns, from -`ONE` to `ONE`
+pub fn cos(angle: u16) -> Q16 {
+    sin(angle.wrapping_add(1 << 14))
+}
+
+/// Sine mapped from 0 to `ONE`, for brightness waves
+pub fn wave(angle: u16) -> Q16 {
+    (sin(angle) + ONE) / 2
+}
+
+/// Linear interpolation, `t` from 0 (`a`) to `ONE` (`b`)
+pub fn lerp(a: Q16, b: Q16, t: Q16) -> Q16 {
+    a + mul(b - a, t)
+}
+
+/// A color channel or brightness times `factor`, which is clamped to 0.0 - 1.0
+pub fn scale_u8(value: u8, factor: Q16) -> u8 {
+    ((value as Q16 * factor.clamp(0, ONE)) >> 16) as u8
+}
+
+/// Integer square root, rounded down
+pub fn isqrt(value: u32) -> u32 {
+    // Digit by digit, one bit of the root at a time
+    let mut rest = value;
+    let mut root = 0;
+    let mut bit = 1 << 30;
+    while bit > rest {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if rest >= root + bit {
+            rest -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sin_matches_libm() {
+        for angle in (0..=u16::MAX).step_by(97) {
+            let expected = libm::sinf(angle as f32 / 65536.0 * core::f32::consts::TAU);
+            let error = (to_f32(sin(angle)) - expected).abs();
+            assert!(error < 1e-4, "sin({angle}) is off by {error}");
+        }
+        assert_eq!(sin(0), 0);
+        assert_eq!(sin(1 << 14), ONE);
+        assert_eq!(cos(1 << 15), -ONE);
+        assert_eq!(wave(3 << 14), 0);
+    }
+
+    #[test]
+    fn integer_helpers() {
+        for value in [0, 1, 2, 3, 4, 15, 16, 17, 99_999, u32::MAX] {
+            let root = isqrt(value) as u64;
+            assert!(root * root <= value as u64 && (root + 1) * (root + 1) > value as u64);
+        }
+
+        assert_eq!(lerp(from_f32(2.0), from_f32(4.0), ONE / 4), from_f32(2.5));
+        assert_eq!(scale_u8(200, ONE / 2), 100);
+        assert_eq!(scale_u8(200, 2 * ONE), 200);
+        assert_eq!(turns(1.25), turns(0.25));
+        assert_eq!(turns(-0.25), turns(0.75));
+        assert_eq!(turns(40_000.25), turns(0.25));
+        assert_eq!(turns(-40_000.75), turns(0.25));
+    }
+}
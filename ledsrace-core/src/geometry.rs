@@ -0,0 +1,179 @@
+use libm::{atan2f, floorf};
+
+use crate::Point;
+
+/// Geometry of a circuit that animations would otherwise recompute every frame, worked out once
+/// when the circuit is created.
+///
+/// Per LED values are in the order of `led_positions`.
+#[derive(Clone, Debug)]
+pub struct CircuitGeometry<const N: usize> {
+    /// Average position of all LEDs
+    pub center: Point,
+    /// Bottom left corner of the bounding box
+    pub min: Point,
+    /// Top right corner of the bounding box
+    pub max: Point,
+    /// Distance from the center to the furthest LED
+    pub max_distance: f32,
+    /// Length of one lap, from the first LED along the track back to the first LED
+    pub track_length: f32,
+    /// Number of LEDs the geometry covers
+    len: usize,
+    distances: [f32; N],
+    angles: [f32; N],
+    normalized: [Point; N],
+    track_distances: [f32; N],
+}
+
+impl<const N: usize> CircuitGeometry<N> {
+    /// Positions past `N` are ignored
+    pub fn new(positions: &[Point]) -> Self {
+        let positions = &positions[..positions.len().min(N)];
+        let mut geometry = Self {
+            center: Point::new(0.0, 0.0),
+            min: Point::new(0.0, 0.0),
+            max: Point::new(0.0, 0.0),
+            max_distance: 0.0,
+            track_length: 0.0,
+            len: positions.len(),
+            distances: [0.0; N],
+            angles: [0.0; N],
+            normalized: [Point::new(0.0, 0.0); N],
+            track_distances: [0.0; N],
+        };
+        let Some(first) = positions.first() else {
+            return geometry;
+        };
+
+        let (mut sum_x, mut sum_y) = (0.0, 0.0);
+        let (mut min, mut max) = (*first, *first);
+        for point in positions {
+            sum_x += point.x;
+            sum_y += point.y;
+            min = Point::new(min.x.min(point.x), min.y.min(point.y));
+            max = Point::new(max.x.max(point.x), max.y.max(point.y));
+        }
+        let center = Point::new(
+            sum_x / positions.len() as f32,
+            sum_y / positions.len() as f32,
+        );
+
+        // Keep a flat circuit from dividing by zero
+        let width = (max.x - min.x).max(f32::EPSILON);
+        let height = (max.y - min.y).max(f32::EPSILON);
+
+        let mut track_length = 0.0;
+        let mut previous = first;
+        for (i, point) in positions.iter().enumerate() {
+            let distance = point.distance_to(&center);
+            geometry.max_distance = geometry.max_distance.max(distance);
+            geometry.distances[i] = distance;
+            geometry.angles[i] = atan2f(point.y - center.y, point.x - center.x);
+            geometry.normalized[i] =
+                Point::new((point.x - min.x) / width, (point.y - min.y) / height);
+
+            track_length += point.distance_to(previous);
+            geometry.track_distances[i] = track_length;
+            previous = point;
+        }
+
+        geometry.center = center;
+        geometry.min = min;
+        geometry.max = max;
+        geometry.track_length = track_length + previous.distance_to(first);
+        geometry
+    }
+
+    /// Distance from the center
+    pub fn distance(&self, index: usize) -> f32 {
+        self.distances[index]
+    }
+
+    /// Angle around the center in radians, from -π to π with 0 pointing along the x axis
+    pub fn angle(&self, index: usize) -> f32 {
+        self.angles[index]
+    }
+
+    /// Position within the bounding box, from (0.0, 0.0) at `min` to (1.0, 1.0) at `max`
+    pub fn normalized(&self, index: usize) -> Point {
+        self.normalized[index]
+    }
+
+    /// Distance along the track from the first LED
+    pub fn track_distance(&self, index: usize) -> f32 {
+        self.track_distances[index]
+    }
+
+    /// The same distance along the track, within one lap
+    pub fn wrap(&self, distance: f32) -> f32 {
+        if self.track_length <= 0.0 {
+            return 0.0;
+        }
+        let wrapped = distance - floorf(distance / self.track_length) * self.track_length;
+        // Rounding can land exactly on the lap length
+        if wrapped >= self.track_length {
+            0.0
+        } else {
+            wrapped
+        }
+    }
+
+    /// The LEDs before and after `distance` along the track, and how far it is from the first
+    /// to the second, 0.0 - 1.0. Distances wrap around the lap.
+    pub fn position_at(&self, distance: f32) -> (usize, usize, f32) {
+        if self.len == 0 {
+            return (0, 0, 0.0);
+        }
+
+        let distance = self.wrap(distance);
+        let track_distances = &self.track_distances[..self.len];
+        let before = track_distances
+            .partition_point(|&d| d <= distance)
+            .saturating_sub(1);
+        let after = (before + 1) % self.len;
+
+        let end = if after == 0 {
+            self.track_length
+        } else {
+            track_distances[after]
+        };
+        let gap = end - track_distances[before];
+        let frac = if gap > 0.0 {
+            (distance - track_distances[before]) / gap
+        } else {
+            0.0
+        };
+        (before, after, frac)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square() {
+        let geometry = CircuitGeometry::<4>::new(&[
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(0.0, 2.0),
+        ]);
+
+        assert_eq!((geometry.center.x, geometry.center.y), (1.0, 1.0));
+        assert_eq!((geometry.max.x, geometry.max.y), (2.0, 2.0));
+        assert_eq!(geometry.max_distance, geometry.distance(2));
+        assert!((geometry.distance(0) - core::f32::consts::SQRT_2).abs() < 1e-6);
+        assert!((geometry.angle(2) - core::f32::consts::FRAC_PI_4).abs() < 1e-6);
+        assert_eq!(geometry.normalized(1).x, 1.0);
+        assert_eq!(geometry.track_distance(3), 6.0);
+        assert_eq!(geometry.track_length, 8.0);
+
+        assert_eq!(geometry.position_at(0.0), (0, 1, 0.0));
+        assert_eq!(geometry.position_at(3.0), (1, 2, 0.5));
+        assert_eq!(geometry.position_at(7.0), (3, 0, 0.5));
+        assert_eq!(geometry.position_at(-1.0), (3, 0, 0.5));
+        assert_eq!(geometry.position_at(10.0), (1, 2, 0.0));
+    }
+}
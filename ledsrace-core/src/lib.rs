@@ -1,10 +1,29 @@
 #![no_std]
 
 pub mod animation;
+pub mod circuits;
+pub mod clock;
+pub mod color;
 pub mod data_frame;
+pub mod diagnostics;
+pub mod dither;
+pub mod driver;
+pub mod fixed;
+pub mod geometry;
+pub mod power;
+pub mod remap;
+pub mod rng;
+pub mod show;
+pub mod stats;
+pub mod track;
+
+pub use circuits::zandvoort;
+pub use color::Color;
+use geometry::CircuitGeometry;
+use track::{Corner, DrsZone, PitLane};
 
 /// Represents a point on the circuit
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Point {
     pub x: f32,
     pub y: f32,
@@ -23,7 +42,7 @@ impl Point {
 }
 
 /// Enum representing the different sectors of the circuit
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Sector {
     _1,
     _2,
@@ -34,6 +53,9 @@ pub enum Sector {
 pub trait Circuit<const N: usize> {
     const LED_COUNT: usize;
 
+    /// Returns the name of the circuit
+    fn name(&self) -> &'static str;
+
     fn led_count(&self) -> usize;
 
     /// Returns the positions of all LEDs on the circuit
@@ -42,21 +64,69 @@ pub trait Circuit<const N: usize> {
     /// Returns the positions of all LEDs in a random order
     fn led_positions_random(&self) -> &'static [Point];
 
+    /// Returns the indices of all LEDs in the random order of `led_positions_random`
+    fn led_order_random(&self) -> &'static [usize];
+
     /// Returns the positions of all LEDs in a specific sector
     fn sectors(&self, sector: Sector) -> &'static [Point];
 
     /// Returns the indices of all LEDs in a specific sector
     fn sector_indices(&self, sector: Sector) -> core::ops::Range<usize>;
 
+    /// Returns the sector an LED is in
+    fn sector_of(&self, led: usize) -> Sector {
+        [Sector::_1, Sector::_2]
+            .into_iter()
+            .find(|&sector| self.sector_indices(sector).contains(&led))
+            .unwrap_or(Sector::_3)
+    }
+
+    /// Returns the LED on the start/finish line
+    fn start_finish(&self) -> usize;
+
+    /// Returns the named corners in track order
+    fn corners(&self) -> &'static [Corner];
+
+    /// Returns the corner with this name, ignoring case
+    fn corner(&self, name: &str) -> Option<&'static Corner> {
+        self.corners()
+            .iter()
+            .find(|corner| corner.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the DRS zones in track order
+    fn drs_zones(&self) -> &'static [DrsZone];
+
+    /// Returns the pit lane, if the circuit has one
+    fn pit_lane(&self) -> Option<PitLane>;
+
     /// Returns a mutable reference to the LED buffer
     fn led_buffer(&mut self) -> &mut LedStateBuffer<N>;
 
     /// Set a specific LED's color and priority
     fn set_led(&mut self, index: usize, color: Color, priority: Priority);
-}
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct Color(pub u8, pub u8, pub u8);
+    /// Set an LED to an 8.8 fixed point color, see `Color::scale_fine`. Fades that write the
+    /// fraction of a step this way stay smooth through `TemporalDither`.
+    fn set_led_fine(&mut self, index: usize, color: [u16; 3], priority: Priority) {
+        self.led_buffer().set_led_fine(index, color, priority);
+    }
+
+    /// Returns the center, distances and track lengths of `led_positions`, computed once
+    fn geometry(&self) -> &CircuitGeometry<N>;
+
+    /// Returns the LEDs before and after `distance` along the track, and how far it is from the
+    /// first to the second (0.0 - 1.0). Distances are in the units of `led_positions` and wrap
+    /// around the lap, so movers keep the same speed where the LEDs are closer together.
+    fn position_at(&self, distance: f32) -> (usize, usize, f32) {
+        self.geometry().position_at(distance)
+    }
+
+    /// Returns the distance of an LED along the track from the first LED
+    fn distance_of(&self, led: usize) -> f32 {
+        self.geometry().track_distance(led)
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Ord, PartialOrd, Eq)]
 pub enum Priority {
@@ -67,12 +137,15 @@ pub enum Priority {
 /// Represents a buffer of LED states that animations can write to
 pub struct LedStateBuffer<const N: usize> {
     states: [(Color, Priority); N],
+    /// Fraction of a step below each channel of `states`, written by `set_led_fine`
+    fractions: [[u8; 3]; N],
 }
 
 impl<const N: usize> LedStateBuffer<N> {
     pub fn new() -> Self {
         Self {
             states: [(Color(0, 0, 0), Priority::Background); N],
+            fractions: [[0; 3]; N],
         }
     }
 
@@ -85,16 +158,57 @@ impl<const N: usize> LedStateBuffer<N> {
         // Only update if new priority is higher
         if priority >= self.states[index].1 {
             self.states[index] = (color, priority);
+            self.fractions[index] = [0; 3];
         }
     }
 
+    /// Set LED state from an 8.8 fixed point color if priority is higher than existing
+    pub fn set_led_fine(&mut self, index: usize, color: [u16; 3], priority: Priority) {
+        if index < N && priority >= self.states[index].1 {
+            self.set_fine_color(index, color);
+            self.states[index].1 = priority;
+        }
+    }
+
+    /// Color of an LED in 8.8 fixed point, including the fraction written by `set_led_fine`
+    pub fn fine_color(&self, index: usize) -> [u16; 3] {
+        let Color(r, g, b) = self.states[index].0;
+        let [fr, fg, fb] = self.fractions[index];
+        [
+            (r as u16) << 8 | fr as u16,
+            (g as u16) << 8 | fg as u16,
+            (b as u16) << 8 | fb as u16,
+        ]
+    }
+
+    /// Replace the color of an LED with an 8.8 fixed point color whatever its priority, for
+    /// output stages that adjust the final frame
+    pub fn set_fine_color(&mut self, index: usize, color: [u16; 3]) {
+        self.states[index].0 = Color(
+            (color[0] >> 8) as u8,
+            (color[1] >> 8) as u8,
+            (color[2] >> 8) as u8,
+        );
+        self.fractions[index] = color.map(|channel| channel as u8);
+    }
+
     /// Clear the buffer to default state
     pub fn clear(&mut self) {
         self.states = [(Color(0, 0, 0), Priority::Background); N];
+        self.fractions = [[0; 3]; N];
     }
 
     /// Get final LED colors for rendering
     pub fn get_colors(&self) -> &[(Color, Priority)] {
         &self.states
     }
+
+    /// Replace the state of an LED whatever its priority, for stages that mix finished frames.
+    /// The color is a whole step, the fraction of `set_led_fine` is dropped.
+    pub fn set_color(&mut self, index: usize, color: Color, priority: Priority) {
+        if index < N {
+            self.states[index] = (color, priority);
+            self.fractions[index] = [0; 3];
+        }
+    }
 }
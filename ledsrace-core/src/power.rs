@@ -0,0 +1,201 @@
+use crate::{Color, LedStateBuffer};
+
+/// Estimated current draw of a single LED
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PowerModel {
+    /// Current in mA drawn by the red, green and blue channel at full duty cycle
+    pub channel_ma: [f32; 3],
+    /// Current in mA drawn by each LED, even when it is off
+    pub idle_ma: f32,
+}
+
+impl PowerModel {
+    /// HD108 driven at the current gain used by the application (regulation level 2, 2.24 mA per channel)
+    pub const HD108_DEFAULT_GAIN: Self = Self::new([2.24, 2.24, 2.24], 0.7);
+
+    pub const fn new(channel_ma: [f32; 3], idle_ma: f32) -> Self {
+        Self {
+            channel_ma,
+            idle_ma,
+        }
+    }
+
+    /// HD108 driven at a 5 bit current gain, as set by `HD108::set_brightness`.
+    /// Gain 0 is regulation level 2, the current is taken to grow linearly with the level.
+    pub fn hd108(gain: u8) -> Self {
+        let channel_ma = Self::HD108_DEFAULT_GAIN.channel_ma[0] * (gain.min(31) as f32 + 2.0) / 2.0;
+        Self::new([channel_ma; 3], Self::HD108_DEFAULT_GAIN.idle_ma)
+    }
+
+    /// Estimated current in mA of a single LED showing `color`
+    pub fn led_ma(&self, color: Color) -> f32 {
+        self.idle_ma
+            + (color.0 as f32 * self.channel_ma[0]
+                + color.1 as f32 * self.channel_ma[1]
+                + color.2 as f32 * self.channel_ma[2])
+                / 255.0
+    }
+
+    /// Estimated current in mA of a whole frame
+    pub fn frame_ma(&self, colors: impl IntoIterator<Item = Color>) -> f32 {
+        colors.into_iter().map(|color| self.led_ma(color)).sum()
+    }
+}
+
+/// Scales frames down so their estimated current stays within a budget.
+///
+/// Brightness is reduced immediately when a frame would exceed the budget, and recovers gradually
+/// over the following frames so that flashing content does not make the limiter pump.
+pub struct PowerLimiter {
+    model: PowerModel,
+    /// Current budget in mA for all LEDs together
+    budget_ma: f32,
+    /// Fraction of the distance back to full brightness recovered per frame (0.0 - 1.0)
+    release: f32,
+    /// Brightness scale applied to the last frame
+    scale: f32,
+}
+
+impl PowerLimiter {
+    pub const fn new(model: PowerModel, budget_ma: f32) -> Self {
+        Self {
+            model,
+            budget_ma,
+            release: 0.05,
+            scale: 1.0,
+        }
+    }
+
+    /// Estimate the next frames with another model, e.g. after the driver gain changed
+    pub fn set_model(&mut self, model: PowerModel) {
+        self.model = model;
+    }
+
+    pub fn set_budget(&mut self, budget_ma: f32) {
+        self.budget_ma = budget_ma;
+    }
+
+    /// Set how fast brightness recovers after a frame exceeded the budget
+    pub fn set_release(&mut self, release: f32) {
+        self.release = release.clamp(0.0, 1.0);
+    }
+
+    /// Brightness scale applied to the last frame (1.0 when not limiting)
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Update the brightness scale for the next frame and return it
+    pub fn update(&mut self, colors: impl IntoIterator<Item = Color>) -> f32 {
+        let mut led_count = 0;
+        let mut total_ma = 0.0;
+        for color in colors {
+            led_count += 1;
+            total_ma += self.model.led_ma(color);
+        }
+
+        // The idle current can't be scaled away, only the part driven by the colors can
+        let idle_ma = self.model.idle_ma * led_count as f32;
+        let target = if total_ma <= self.budget_ma {
+            1.0
+        } else if self.budget_ma <= idle_ma {
+            0.0
+        } else {
+            (self.budget_ma - idle_ma) / (total_ma - idle_ma)
+        };
+
+        if target < self.scale {
+            self.scale = target;
+        } else {
+            self.scale += (target - self.scale) * self.release;
+        }
+
+        self.scale
+    }
+
+    /// Limit the frame in `buffer` in place, returns the brightness scale that was applied
+    pub fn apply<const N: usize>(&mut self, buffer: &mut LedStateBuffer<N>) -> f32 {
+        let scale = self.update(buffer.get_colors().iter().map(|(color, _)| *color));
+        if scale < 1.0 {
+            for index in 0..N {
+                let color = buffer.fine_color(index);
+                buffer.set_fine_color(index, color.map(|channel| (channel as f32 * scale) as u16));
+            }
+        }
+        scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Priority;
+
+    const MODEL: PowerModel = PowerModel::new([20.0, 20.0, 20.0], 1.0);
+
+    #[test]
+    fn estimate_frame_current() {
+        let white = [Color(255, 255, 255); 10];
+        assert_eq!(MODEL.frame_ma(white), 610.0);
+
+        let off = [Color(0, 0, 0); 10];
+        assert_eq!(MODEL.frame_ma(off), 10.0);
+
+        let half_red = [Color(51, 0, 0); 10];
+        assert_eq!(MODEL.frame_ma(half_red), 50.0);
+    }
+
+    #[test]
+    fn hd108_current_follows_gain() {
+        assert_eq!(PowerModel::hd108(0), PowerModel::HD108_DEFAULT_GAIN);
+        assert_eq!(PowerModel::hd108(2).channel_ma, [4.48; 3]);
+        assert_eq!(PowerModel::hd108(255), PowerModel::hd108(31));
+    }
+
+    #[test]
+    fn limit_to_budget() {
+        let mut limiter = PowerLimiter::new(MODEL, 310.0);
+        let mut buffer = LedStateBuffer::<10>::new();
+        for i in 0..10 {
+            buffer.set_led(i, Color(255, 255, 255), Priority::Normal);
+        }
+
+        let scale = limiter.apply(&mut buffer);
+        assert_eq!(scale, 0.5);
+
+        let limited = MODEL.frame_ma(buffer.get_colors().iter().map(|(c, _)| *c));
+        assert!(limited <= 310.0);
+        assert_eq!(buffer.get_colors()[0].0, Color(127, 127, 127));
+    }
+
+    #[test]
+    fn within_budget_is_untouched() {
+        let mut limiter = PowerLimiter::new(MODEL, 1000.0);
+        let mut buffer = LedStateBuffer::<10>::new();
+        buffer.set_led(3, Color(200, 100, 50), Priority::Normal);
+
+        assert_eq!(limiter.apply(&mut buffer), 1.0);
+        assert_eq!(buffer.get_colors()[3].0, Color(200, 100, 50));
+    }
+
+    #[test]
+    fn recovers_gradually() {
+        let mut limiter = PowerLimiter::new(MODEL, 310.0);
+        let white = [Color(255, 255, 255); 10];
+        let off = [Color(0, 0, 0); 10];
+
+        assert_eq!(limiter.update(white), 0.5);
+
+        // Brightness creeps back up instead of jumping to full
+        let first = limiter.update(off);
+        assert!(first > 0.5 && first < 0.6);
+        let mut scale = first;
+        for _ in 0..200 {
+            scale = limiter.update(off);
+        }
+        assert!(scale > 0.99);
+
+        // A bright frame is limited again straight away
+        assert_eq!(limiter.update(white), 0.5);
+    }
+}
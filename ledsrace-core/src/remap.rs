@@ -0,0 +1,82 @@
+use crate::Color;
+
+/// Maps logical LED indices (track order, as used by animations) to their position in the LED chain.
+///
+/// Board revisions can route the chain in a different order than the track, or have LEDs that
+/// should stay dark, without the animations having to know about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LedRemap<const N: usize> {
+    /// Chain index for every logical LED, `None` for LEDs that are disabled
+    table: [Option<u16>; N],
+}
+
+impl<const N: usize> LedRemap<N> {
+    /// Chain order equals track order
+    pub const fn identity() -> Self {
+        let mut table = [None; N];
+        let mut i = 0;
+        while i < N {
+            table[i] = Some(i as u16);
+            i += 1;
+        }
+        Self { table }
+    }
+
+    pub const fn from_table(table: [Option<u16>; N]) -> Self {
+        Self { table }
+    }
+
+    /// Disable a logical LED, it will always be turned off
+    pub const fn disable(mut self, logical: usize) -> Self {
+        self.table[logical] = None;
+        self
+    }
+
+    /// Chain index of a logical LED, `None` if it is disabled
+    pub fn physical_index(&self, logical: usize) -> Option<usize> {
+        self.table.get(logical).copied().flatten().map(usize::from)
+    }
+
+    /// Reorder a frame from track order to chain order. Disabled LEDs and chain positions without
+    /// a logical LED are turned off.
+    pub fn apply(&self, logical: impl IntoIterator<Item = Color>) -> [Color; N] {
+        let mut physical = [Color(0, 0, 0); N];
+        for (index, color) in logical.into_iter().enumerate().take(N) {
+            if let Some(led) = self.physical_index(index).and_then(|i| physical.get_mut(i)) {
+                *led = color;
+            }
+        }
+        physical
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color(255, 0, 0);
+    const GREEN: Color = Color(0, 255, 0);
+    const BLUE: Color = Color(0, 0, 255);
+    const OFF: Color = Color(0, 0, 0);
+
+    #[test]
+    fn identity_keeps_order() {
+        let remap = LedRemap::<3>::identity();
+        assert_eq!(remap.apply([RED, GREEN, BLUE]), [RED, GREEN, BLUE]);
+        assert_eq!(remap.physical_index(2), Some(2));
+        assert_eq!(remap.physical_index(3), None);
+    }
+
+    #[test]
+    fn reorder_and_disable() {
+        // Chain runs backwards, and the last logical LED isn't fitted
+        let remap = LedRemap::from_table([Some(3), Some(2), Some(1), Some(0)]).disable(3);
+
+        assert_eq!(
+            remap.apply([RED, GREEN, BLUE, RED]),
+            [OFF, BLUE, GREEN, RED]
+        );
+        assert_eq!(remap.physical_index(0), Some(3));
+        assert_eq!(remap.physical_index(3), None);
+    }
+}
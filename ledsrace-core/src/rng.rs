@@ -0,0 +1,111 @@
+//! Seeded pseudo random numbers, so animations look random but render the same on every run.
+
+use core::ops::Range;
+
+/// Xorshift32 generator, fast without an FPU and good enough for visuals. Not for cryptography.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    /// Any seed works, nearby seeds give unrelated sequences
+    pub const fn new(seed: u32) -> Self {
+        // Scramble the seed, xorshift passes simple seeds through to its first numbers
+        let mut z = seed.wrapping_add(0x9E37_79B9);
+        z = (z ^ (z >> 16)).wrapping_mul(0x85EB_CA6B);
+        z = (z ^ (z >> 13)).wrapping_mul(0xC2B2_AE35);
+        z ^= z >> 16;
+
+        // Xorshift gets stuck at zero
+        Self {
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    pub const fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+
+    /// Number in `range`, or its start if the range is empty
+    pub const fn range(&mut self, range: Range<u32>) -> u32 {
+        let span = range.end.saturating_sub(range.start);
+        // Multiply instead of modulo, that keeps the low quality low bits out of the result
+        range.start + ((self.next_u32() as u64 * span as u64) >> 32) as u32
+    }
+
+    /// Number from 0.0 up to 1.0
+    pub fn float(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Number in `range`
+    pub fn float_range(&mut self, range: Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.float()
+    }
+
+    /// True with probability `p` from 0.0 to 1.0
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.float() < p
+    }
+
+    /// A random item, `None` if there are none
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        let index = self.range(0..items.len() as u32) as usize;
+        items.get(index)
+    }
+
+    /// Put the items in a random order, also at compile time
+    pub const fn shuffle<T>(&mut self, items: &mut [T]) {
+        // Fisher-Yates
+        let mut i = items.len();
+        while i > 1 {
+            i -= 1;
+            let j = self.range(0..i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Rng::new(0);
+        let mut b = Rng::new(0);
+        let mut c = Rng::new(1);
+        for _ in 0..100 {
+            let n = a.next_u32();
+            assert_eq!(n, b.next_u32());
+            assert_ne!(n, c.next_u32());
+        }
+    }
+
+    #[test]
+    fn helpers_stay_in_range() {
+        let mut rng = Rng::new(42);
+        let mut seen = [false; 10];
+        for _ in 0..1000 {
+            let n = rng.range(5..15);
+            assert!((5..15).contains(&n));
+            seen[n as usize - 5] = true;
+
+            let f = rng.float_range(-1.0..1.0);
+            assert!((-1.0..1.0).contains(&f));
+        }
+        assert!(seen.iter().all(|&seen| seen));
+        assert_eq!(rng.range(3..3), 3);
+        assert_eq!(rng.choose::<u8>(&[]), None);
+
+        let mut items = [0, 1, 2, 3, 4, 5, 6, 7];
+        rng.shuffle(&mut items);
+        assert_ne!(items, [0, 1, 2, 3, 4, 5, 6, 7]);
+        items.sort();
+        assert_eq!(items, [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+}
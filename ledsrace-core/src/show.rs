@@ -0,0 +1,460 @@
+//! Compiled show descriptions.
+//!
+//! A show is a playlist of animations with their parameters, written as text and compiled on the
+//! host by `ledsrace-show` into a blob that the firmware loads into an [`AnimationQueue`]. The blob
+//! is a [`ShowHeader`] followed by its [`EntrySpec`]s, each encoded with bincode.
+
+use core::fmt;
+
+use bincode::{Decode, Encode};
+use embassy_time::Duration;
+
+use crate::{
+    animation::{
+        AnimationQueue, Animations, CircuitPulse, DutchFlag, GhostCar, GrowingTrail,
+        LightningSprint, MexicanWave, OvertakeDuel, PlaylistEntry, RainDropRace, ShowSectors,
+        StaticColor, SunsetGlow, Transition, UnicornRainbow, Wipe,
+    },
+    color::Palette,
+    Color,
+};
+
+/// Start of every show blob
+pub const SHOW_MAGIC: [u8; 4] = *b"LRSH";
+
+/// Version of the blob layout, bumped on every incompatible change
+pub const SHOW_VERSION: u8 = 3;
+
+/// An animation with its parameters
+#[derive(Clone, Copy, Debug, PartialEq, Encode, Decode)]
+pub enum AnimationSpec {
+    Sunset,
+    Static {
+        color: Color,
+    },
+    Sectors {
+        colors: [Color; 3],
+    },
+    /// `duration_ms` 0 rains forever
+    RainDrop {
+        duration_ms: u32,
+    },
+    /// `duration_ms` 0 parties forever
+    Party {
+        duration_ms: u32,
+    },
+    /// Speeds in LEDs per second
+    Overtake {
+        fast_speed: f32,
+        slow_speed: f32,
+    },
+    GhostCar {
+        speed: f32,
+        length: u8,
+        color: Color,
+    },
+    Lightning {
+        color: Color,
+        speed: f32,
+    },
+    MexicanWave {
+        speed: f32,
+        width: f32,
+        color: Color,
+    },
+    Unicorn {
+        speed: f32,
+        wavelength: f32,
+    },
+    DutchFlag,
+    Pulse {
+        speed: f32,
+        width: f32,
+        colors: [Color; 3],
+        /// `None` for a single pulse, otherwise a new pulse starts every interval and the
+        /// animation doesn't finish
+        interval_ms: Option<u32>,
+        random: bool,
+    },
+    /// The head moves at `speed` and the trail behind it grows at `grow_speed`, both in LEDs per
+    /// second
+    GrowingTrail {
+        color: Color,
+        speed: f32,
+        grow_speed: f32,
+    },
+}
+
+impl AnimationSpec {
+    /// Check the parameters, so a show that compiles also runs
+    pub fn validate(&self) -> Result<(), ShowErrorKind> {
+        let positive = |value: f32, message| {
+            if value.is_finite() && value > 0.0 {
+                Ok(())
+            } else {
+                Err(ShowErrorKind::Invalid(message))
+            }
+        };
+
+        match *self {
+            AnimationSpec::Overtake {
+                fast_speed,
+                slow_speed,
+            } => {
+                positive(fast_speed, "fast speed must be above zero")?;
+                positive(slow_speed, "slow speed must be above zero")?;
+                if fast_speed <= slow_speed {
+                    return Err(ShowErrorKind::Invalid(
+                        "fast speed must be above slow speed",
+                    ));
+                }
+                Ok(())
+            }
+            AnimationSpec::GhostCar { speed, length, .. } => {
+                positive(speed, "speed must be above zero")?;
+                if length == 0 {
+                    return Err(ShowErrorKind::Invalid("length must be above zero"));
+                }
+                Ok(())
+            }
+            AnimationSpec::Lightning { speed, .. } => positive(speed, "speed must be above zero"),
+            AnimationSpec::MexicanWave { speed, width, .. } => {
+                positive(speed, "speed must be above zero")?;
+                positive(width, "width must be above zero")
+            }
+            AnimationSpec::Unicorn { speed, wavelength } => {
+                positive(speed, "speed must be above zero")?;
+                positive(wavelength, "wavelength must be above zero")
+            }
+            AnimationSpec::Pulse {
+                speed,
+                width,
+                interval_ms,
+                ..
+            } => {
+                positive(speed, "speed must be above zero")?;
+                positive(width, "width must be above zero")?;
+                if interval_ms == Some(0) {
+                    return Err(ShowErrorKind::Invalid("interval must be above zero"));
+                }
+                Ok(())
+            }
+            AnimationSpec::GrowingTrail {
+                speed, grow_speed, ..
+            } => {
+                positive(speed, "speed must be above zero")?;
+                positive(grow_speed, "grow speed must be above zero")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn build(&self, led_count: usize) -> Animations {
+        match *self {
+            AnimationSpec::Sunset => Animations::Sunset(SunsetGlow::new()),
+            AnimationSpec::Static { color } => Animations::Static(StaticColor::new(color)),
+            AnimationSpec::Sectors {
+                colors: [s1, s2, s3],
+            } => Animations::ShowSectors(ShowSectors::new(s1, s2, s3)),
+            AnimationSpec::RainDrop { duration_ms } => {
+                Animations::RainDrop(RainDropRace::new(millis(duration_ms)))
+            }
+            AnimationSpec::Party { duration_ms } => {
+                Animations::RainDrop(RainDropRace::party(millis(duration_ms)))
+            }
+            AnimationSpec::Overtake {
+                fast_speed,
+                slow_speed,
+            } => Animations::OvertakeDuel(
+                OvertakeDuel::new(led_count).with_speeds(fast_speed, slow_speed),
+            ),
+            AnimationSpec::GhostCar {
+                speed,
+                length,
+                color,
+            } => Animations::GhostCar(GhostCar::new(speed, length as usize, color)),
+            AnimationSpec::Lightning { color, speed } => {
+                Animations::LightningSprint(LightningSprint::new(color).with_speed(speed))
+            }
+            AnimationSpec::MexicanWave {
+                speed,
+                width,
+                color,
+            } => Animations::MexicanWave(MexicanWave::new(speed, width, color)),
+            AnimationSpec::Unicorn { speed, wavelength } => {
+                Animations::UnicornRainbow(UnicornRainbow::new(speed, wavelength))
+            }
+            AnimationSpec::DutchFlag => Animations::DutchFlag(DutchFlag::new()),
+            AnimationSpec::Pulse {
+                speed,
+                width,
+                colors,
+                interval_ms,
+                random,
+            } => Animations::CircuitPulse(CircuitPulse::new(
+                speed,
+                width,
+                Palette::new(&colors),
+                interval_ms.map(millis),
+                random,
+            )),
+            AnimationSpec::GrowingTrail {
+                color,
+                speed,
+                grow_speed,
+            } => Animations::GrowingTrail(GrowingTrail::new(color, grow_speed).with_speed(speed)),
+        }
+    }
+}
+
+/// How the show switches from one animation to the next
+#[derive(Clone, Copy, Debug, PartialEq, Encode, Decode)]
+pub enum TransitionSpec {
+    Cut,
+    Crossfade { duration_ms: u32 },
+    FadeThroughBlack { duration_ms: u32 },
+    Wipe { wipe: Wipe, duration_ms: u32 },
+}
+
+impl From<TransitionSpec> for Transition {
+    fn from(spec: TransitionSpec) -> Self {
+        match spec {
+            TransitionSpec::Cut => Transition::Cut,
+            TransitionSpec::Crossfade { duration_ms } => Transition::Crossfade(millis(duration_ms)),
+            TransitionSpec::FadeThroughBlack { duration_ms } => {
+                Transition::FadeThroughBlack(millis(duration_ms))
+            }
+            TransitionSpec::Wipe { wipe, duration_ms } => {
+                Transition::Wipe(wipe, millis(duration_ms))
+            }
+        }
+    }
+}
+
+/// Settings of the whole show
+#[derive(Clone, Copy, Debug, PartialEq, Encode, Decode)]
+pub struct ShowHeader {
+    pub magic: [u8; 4],
+    pub version: u8,
+    /// Duration of entries that don't set their own
+    pub duration_ms: u32,
+    pub transition: TransitionSpec,
+    /// Seed of the shuffle, `None` to play the entries in order
+    pub shuffle: Option<u32>,
+    /// Number of entries following the header
+    pub entries: u16,
+}
+
+/// A playlist entry, with the source line it was written on
+#[derive(Clone, Copy, Debug, PartialEq, Encode, Decode)]
+pub struct EntrySpec {
+    pub line: u16,
+    pub animation: AnimationSpec,
+    /// `None` to play until the animation finishes
+    pub duration_ms: Option<u32>,
+    pub repeat: u8,
+    pub weight: u8,
+}
+
+impl EntrySpec {
+    pub fn validate(&self) -> Result<(), ShowError> {
+        let error = |kind| ShowError {
+            line: Some(self.line),
+            kind,
+        };
+
+        self.animation.validate().map_err(error)?;
+        if self.repeat == 0 {
+            return Err(error(ShowErrorKind::Invalid("repeat must be above zero")));
+        }
+        if self.duration_ms == Some(0) {
+            return Err(error(ShowErrorKind::Invalid("duration must be above zero")));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShowErrorKind {
+    /// The blob could not be decoded or encoded
+    Corrupt,
+    /// The blob was compiled for another version of the firmware
+    Version(u8),
+    /// The show has more entries than the queue can hold
+    QueueFull,
+    Invalid(&'static str),
+}
+
+/// Why a show can't be loaded, with the source line of the entry at fault
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShowError {
+    /// `None` for errors in the header
+    pub line: Option<u16>,
+    pub kind: ShowErrorKind,
+}
+
+impl ShowError {
+    const fn header(kind: ShowErrorKind) -> Self {
+        Self { line: None, kind }
+    }
+}
+
+impl fmt::Display for ShowErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShowErrorKind::Corrupt => write!(f, "show data is corrupt"),
+            ShowErrorKind::Version(version) => write!(
+                f,
+                "show is version {}, expected version {}",
+                version, SHOW_VERSION
+            ),
+            ShowErrorKind::QueueFull => write!(f, "too many entries"),
+            ShowErrorKind::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl fmt::Display for ShowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+fn config() -> impl bincode::config::Config {
+    bincode::config::standard()
+}
+
+fn millis(ms: u32) -> Duration {
+    Duration::from_millis(ms as u64)
+}
+
+/// Encode a show into `buf`, returning the number of bytes written
+pub fn write_show(
+    header: &ShowHeader,
+    entries: &[EntrySpec],
+    buf: &mut [u8],
+) -> Result<usize, ShowError> {
+    let corrupt = |_| ShowError::header(ShowErrorKind::Corrupt);
+
+    let mut len = bincode::encode_into_slice(header, buf, config()).map_err(corrupt)?;
+    for entry in entries {
+        len += bincode::encode_into_slice(entry, &mut buf[len..], config()).map_err(corrupt)?;
+    }
+    Ok(len)
+}
+
+/// Load a compiled show into a queue, checking every entry
+pub fn load_show<const CAP: usize>(
+    blob: &[u8],
+    led_count: usize,
+) -> Result<AnimationQueue<CAP>, ShowError> {
+    let (header, mut offset): (ShowHeader, usize) = bincode::decode_from_slice(blob, config())
+        .map_err(|_| ShowError::header(ShowErrorKind::Corrupt))?;
+    if header.magic != SHOW_MAGIC {
+        return Err(ShowError::header(ShowErrorKind::Corrupt));
+    }
+    if header.version != SHOW_VERSION {
+        return Err(ShowError::header(ShowErrorKind::Version(header.version)));
+    }
+
+    let mut queue =
+        AnimationQueue::new(millis(header.duration_ms)).with_transition(header.transition.into());
+    if let Some(seed) = header.shuffle {
+        queue = queue.with_shuffle(seed);
+    }
+
+    let mut line = None;
+    for _ in 0..header.entries {
+        // A corrupt entry is reported after the last entry that could be read
+        let (entry, len): (EntrySpec, usize) =
+            bincode::decode_from_slice(&blob[offset..], config()).map_err(|_| ShowError {
+                line,
+                kind: ShowErrorKind::Corrupt,
+            })?;
+        offset += len;
+        line = Some(entry.line);
+
+        entry.validate()?;
+        if queue.is_full() {
+            return Err(ShowError {
+                line,
+                kind: ShowErrorKind::QueueFull,
+            });
+        }
+
+        let mut playlist_entry = PlaylistEntry::new(entry.animation.build(led_count))
+            .with_repeat(entry.repeat)
+            .with_weight(entry.weight);
+        if let Some(duration_ms) = entry.duration_ms {
+            playlist_entry = playlist_entry.with_duration(millis(duration_ms));
+        }
+        queue.add_entry(playlist_entry);
+    }
+
+    Ok(queue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: ShowHeader = ShowHeader {
+        magic: SHOW_MAGIC,
+        version: SHOW_VERSION,
+        duration_ms: 5000,
+        transition: TransitionSpec::Crossfade { duration_ms: 800 },
+        shuffle: None,
+        entries: 2,
+    };
+
+    const fn entry(line: u16, animation: AnimationSpec) -> EntrySpec {
+        EntrySpec {
+            line,
+            animation,
+            duration_ms: Some(5000),
+            repeat: 1,
+            weight: 1,
+        }
+    }
+
+    #[test]
+    fn load_reports_the_line_at_fault() {
+        let mut buf = [0; 256];
+        let entries = [
+            entry(3, AnimationSpec::DutchFlag),
+            entry(
+                4,
+                AnimationSpec::GhostCar {
+                    speed: 20.0,
+                    length: 8,
+                    color: Color(255, 20, 0),
+                },
+            ),
+        ];
+        let len = write_show(&HEADER, &entries, &mut buf).unwrap();
+        assert!(load_show::<2>(&buf[..len], 216).is_ok());
+
+        let full = load_show::<1>(&buf[..len], 216).err().unwrap();
+        assert_eq!(full.line, Some(4));
+        assert_eq!(full.kind, ShowErrorKind::QueueFull);
+
+        let truncated = load_show::<2>(&buf[..len - 2], 216).err().unwrap();
+        assert_eq!(truncated.line, Some(3));
+        assert_eq!(truncated.kind, ShowErrorKind::Corrupt);
+
+        let mut invalid = entries;
+        invalid[1].repeat = 0;
+        let len = write_show(&HEADER, &invalid, &mut buf).unwrap();
+        let error = load_show::<2>(&buf[..len], 216).err().unwrap();
+        assert_eq!(
+            error,
+            ShowError {
+                line: Some(4),
+                kind: ShowErrorKind::Invalid("repeat must be above zero")
+            }
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use core::fmt;
+
+use embassy_time::Duration;
+
+/// Timing of the render and output loop, accumulated between reports
+#[derive(Default, Debug, PartialEq)]
+pub struct FrameStats {
+    frames: u32,
+    /// Frames that weren't rendered and sent within their frame time
+    dropped: u32,
+    render_total_us: u64,
+    render_max_us: u64,
+    transfer_total_us: u64,
+    transfer_max_us: u64,
+}
+
+impl FrameStats {
+    pub const fn new() -> Self {
+        Self {
+            frames: 0,
+            dropped: 0,
+            render_total_us: 0,
+            render_max_us: 0,
+            transfer_total_us: 0,
+            transfer_max_us: 0,
+        }
+    }
+
+    /// Record the timing of a single frame
+    pub fn record(&mut self, render: Duration, transfer: Duration, missed_deadline: bool) {
+        self.frames += 1;
+        if missed_deadline {
+            self.dropped += 1;
+        }
+
+        self.render_total_us += render.as_micros();
+        self.render_max_us = self.render_max_us.max(render.as_micros());
+        self.transfer_total_us += transfer.as_micros();
+        self.transfer_max_us = self.transfer_max_us.max(transfer.as_micros());
+    }
+
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    pub fn dropped_frames(&self) -> u32 {
+        self.dropped
+    }
+
+    pub fn render_avg_us(&self) -> u64 {
+        self.render_total_us / (self.frames.max(1) as u64)
+    }
+
+    pub fn render_max_us(&self) -> u64 {
+        self.render_max_us
+    }
+
+    pub fn transfer_avg_us(&self) -> u64 {
+        self.transfer_total_us / (self.frames.max(1) as u64)
+    }
+
+    pub fn transfer_max_us(&self) -> u64 {
+        self.transfer_max_us
+    }
+
+    /// Start a new reporting period
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frames: {}, dropped: {}, render: {} µs avg / {} µs max, transfer: {} µs avg / {} µs max",
+            self.frames,
+            self.dropped,
+            self.render_avg_us(),
+            self.render_max_us,
+            self.transfer_avg_us(),
+            self.transfer_max_us
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulate_frames() {
+        let us = Duration::from_micros;
+        let mut stats = FrameStats::new();
+        stats.record(us(3000), us(800), false);
+        stats.record(us(5000), us(900), false);
+        stats.record(us(25000), us(700), true);
+
+        assert_eq!(stats.frames(), 3);
+        assert_eq!(stats.dropped_frames(), 1);
+        assert_eq!(stats.render_avg_us(), 11000);
+        assert_eq!(stats.render_max_us(), 25000);
+        assert_eq!(stats.transfer_avg_us(), 800);
+        assert_eq!(stats.transfer_max_us(), 900);
+
+        stats.reset();
+        assert_eq!(stats, FrameStats::new());
+        assert_eq!(stats.render_avg_us(), 0);
+    }
+}
@@ -0,0 +1,89 @@
+//! Named features of a circuit, such as corners, DRS zones and the pit lane, as LED indices in
+//! track order. They come from the circuit descriptors, see `circuits.rs`.
+
+/// LEDs from `first` up to and including `last` in track order, wrapping past the last LED of the
+/// circuit when `last` is before `first`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub first: usize,
+    pub last: usize,
+}
+
+impl Segment {
+    pub const fn new(first: usize, last: usize) -> Self {
+        Self { first, last }
+    }
+
+    /// Number of LEDs in the segment on a circuit of `led_count` LEDs
+    pub fn len(&self, led_count: usize) -> usize {
+        (self.last + led_count - self.first) % led_count + 1
+    }
+
+    pub fn contains(&self, led: usize, led_count: usize) -> bool {
+        (led + led_count - self.first) % led_count < self.len(led_count)
+    }
+
+    /// The LED indices in track order
+    pub fn indices(&self, led_count: usize) -> impl Iterator<Item = usize> {
+        let first = self.first;
+        (0..self.len(led_count)).map(move |i| (first + i) % led_count)
+    }
+}
+
+/// A named corner
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Corner {
+    pub name: &'static str,
+    pub segment: Segment,
+}
+
+/// Where DRS may be used. A car within a second of the car ahead at the detection LED may open
+/// its rear wing in the activation zone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DrsZone {
+    pub detection: usize,
+    pub activation: Segment,
+}
+
+/// The pit lane, it has no LEDs of its own but runs next to the track from the LED of the entry to
+/// the LED of the exit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PitLane {
+    pub entry: usize,
+    pub exit: usize,
+}
+
+impl PitLane {
+    pub const fn new(entry: usize, exit: usize) -> Self {
+        Self { entry, exit }
+    }
+
+    /// The track LEDs alongside the pit lane
+    pub const fn segment(&self) -> Segment {
+        Segment::new(self.entry, self.exit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{zandvoort::Zandvoort, Circuit};
+
+    #[test]
+    fn segments_wrap_around_the_lap() {
+        let segment = Segment::new(214, 2);
+        assert_eq!(segment.len(216), 5);
+        assert!(segment.contains(0, 216));
+        assert!(!segment.contains(3, 216));
+        assert!(segment.indices(216).eq([214, 215, 0, 1, 2]));
+
+        let zandvoort: Zandvoort<216> = Zandvoort::new();
+        let tarzan = zandvoort.corner("tarzanbocht").unwrap();
+        assert_eq!(tarzan.name, "Tarzanbocht");
+        assert!(zandvoort
+            .pit_lane()
+            .unwrap()
+            .segment()
+            .contains(zandvoort.start_finish(), 216));
+    }
+}
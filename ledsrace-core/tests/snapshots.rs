@@ -0,0 +1,171 @@
+//! Renders every animation at fixed timestamps and compares the frames with the ones stored in
+//! `tests/snapshots`, so a change in how an animation looks doesn't go unnoticed.
+//!
+//! After an intended change, write the new frames and review them in the diff:
+//!
+//! ```bash
+//! UPDATE_SNAPSHOTS=1 cargo test --test snapshots
+//! ```
+
+use std::{fmt::Write, fs, path::PathBuf};
+
+use embassy_time::Duration;
+use ledsrace_core::{animation::*, color::Palette, zandvoort::Zandvoort, Circuit, Color};
+
+const LED_COUNT: usize = 216;
+/// Step between updates, the frame rate of the firmware
+const FRAME_TIME: Duration = Duration::from_millis(20);
+/// Times of the stored frames, in milliseconds
+const TIMESTAMPS: [u64; 4] = [0, 260, 1000, 3000];
+const LEDS_PER_LINE: usize = 12;
+
+static RACE: &[u8] = include_bytes!("../races/zandvoort_2024_20x20_5hz.bin");
+
+/// One of every `Animations` variant, with the parameters the firmware uses
+fn animations() -> Vec<(&'static str, Animations)> {
+    let mut frames = SectorFrames::new(Duration::from_millis(500));
+    frames.add_frame([Color::RED, Color::WHITE, Color::BLUE]);
+    frames.add_frame([Color::BLUE, Color::RED, Color::WHITE]);
+
+    vec![
+        ("sunset", Animations::Sunset(SunsetGlow::new())),
+        (
+            "static",
+            Animations::Static(StaticColor::new(Color::ORANGE)),
+        ),
+        (
+            "sectors",
+            Animations::ShowSectors(ShowSectors::new(Color::RED, Color::WHITE, Color::BLUE)),
+        ),
+        ("sector-frames", Animations::SectorFrames(frames)),
+        (
+            "raindrop",
+            Animations::RainDrop(RainDropRace::new(Duration::from_millis(0))),
+        ),
+        (
+            "party",
+            Animations::RainDrop(RainDropRace::party(Duration::from_millis(0))),
+        ),
+        (
+            "overtake",
+            Animations::OvertakeDuel(OvertakeDuel::new(LED_COUNT)),
+        ),
+        (
+            "ghost-car",
+            Animations::GhostCar(GhostCar::new(20.0, 8, Color::ORANGE)),
+        ),
+        (
+            "lightning",
+            Animations::LightningSprint(LightningSprint::new(Color::ORANGE)),
+        ),
+        (
+            "mexican-wave",
+            Animations::MexicanWave(MexicanWave::new(0.5, 20.0, Color::ORANGE)),
+        ),
+        (
+            "unicorn",
+            Animations::UnicornRainbow(UnicornRainbow::new(0.2, 60.0)),
+        ),
+        ("dutch-flag", Animations::DutchFlag(DutchFlag::new())),
+        (
+            "pulse",
+            Animations::CircuitPulse(CircuitPulse::new(
+                30.0,
+                10.0,
+                Palette::KINGSDAY,
+                Some(Duration::from_millis(5000)),
+                true,
+            )),
+        ),
+        (
+            "growing-trail",
+            Animations::GrowingTrail(GrowingTrail::new(Color::ORANGE, 60.0)),
+        ),
+        (
+            "race-playback",
+            Animations::RacePlayback(RacePlayback::new(RACE, Duration::from_millis(50), |n| {
+                Some(Palette::TEAMS_2024.color(n as usize))
+            })),
+        ),
+    ]
+}
+
+/// The frames at `TIMESTAMPS`, as text
+fn render(mut animation: Animations) -> String {
+    let mut zandvoort: Zandvoort<LED_COUNT> = Zandvoort::new();
+    let mut snapshot = String::new();
+    let mut time = 0;
+
+    for timestamp in TIMESTAMPS {
+        while time < timestamp {
+            animation.update(&zandvoort, FRAME_TIME);
+            time += FRAME_TIME.as_millis();
+        }
+
+        zandvoort.led_buffer().clear();
+        animation.render(&mut zandvoort);
+
+        writeln!(snapshot, "# {timestamp}ms").unwrap();
+        let colors = zandvoort.led_buffer().get_colors();
+        for line in colors.chunks(LEDS_PER_LINE) {
+            let line: Vec<_> = line
+                .iter()
+                .map(|(Color(r, g, b), _)| format!("{r:02x}{g:02x}{b:02x}"))
+                .collect();
+            writeln!(snapshot, "{}", line.join(" ")).unwrap();
+        }
+    }
+    snapshot
+}
+
+/// Where the first difference is, to point at in the failure
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut timestamp = "";
+    for (n, (expected, actual)) in expected.lines().zip(actual.lines()).enumerate() {
+        if expected.starts_with('#') {
+            timestamp = expected;
+        }
+        if expected != actual {
+            return format!(
+                "line {}, frame {timestamp}\n  expected: {expected}\n  actual:   {actual}",
+                n + 1
+            );
+        }
+    }
+    "different number of lines".to_string()
+}
+
+#[test]
+fn animations_match_snapshots() {
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    if update {
+        fs::create_dir_all(&dir).unwrap();
+    }
+
+    let mut failures = Vec::new();
+    for (name, animation) in animations() {
+        let path = dir.join(format!("{name}.txt"));
+        let actual = render(animation);
+
+        if update {
+            fs::write(&path, &actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => {
+                failures.push(format!("{name}: {}", first_difference(&expected, &actual)))
+            }
+            Err(error) => failures.push(format!("{name}: can't read {}: {error}", path.display())),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} animation(s) differ from their snapshots, run with UPDATE_SNAPSHOTS=1 if that is \
+         intended:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,98 @@
+//! Compiles a show description into the blob the firmware loads, see `parse` for the format.
+//!
+//! ```bash
+//! cargo run -- ../ledsrace-application/shows/kingsday.show
+//! ```
+
+use std::{path::PathBuf, process::ExitCode};
+
+mod parse;
+
+const USAGE: &str = "\
+Usage: ledsrace-show <source> [options]
+
+Options:
+  --out <file>  File to write the compiled show to [default: <source> with extension bin]
+  --check       Only check the source for mistakes";
+
+struct Options {
+    source: PathBuf,
+    out: Option<PathBuf>,
+    check: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut source = None;
+    let mut out = None;
+    let mut check = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--out" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for {arg}"))?;
+                out = Some(PathBuf::from(value));
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {arg}")),
+            _ if source.is_none() => source = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument: {arg}")),
+        }
+    }
+
+    let source = source.ok_or("No source given")?;
+    Ok(Options { source, out, check })
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let name = options.source.display();
+    let source = std::fs::read_to_string(&options.source)
+        .map_err(|error| format!("Failed to read {name}: {error}"))?;
+
+    // Errors in the style of a compiler, so editors can jump to the line
+    let show = parse::parse(&source)
+        .map_err(|error| format!("{name}:{}: {}", error.line, error.message))?;
+    let bytes = show.to_bytes()?;
+
+    if options.check {
+        println!(
+            "{name}: {} entries, {} bytes",
+            show.entries.len(),
+            bytes.len()
+        );
+        return Ok(());
+    }
+
+    let out = options
+        .out
+        .clone()
+        .unwrap_or_else(|| options.source.with_extension("bin"));
+    std::fs::write(&out, &bytes)
+        .map_err(|error| format!("Failed to write {}: {error}", out.display()))?;
+    println!(
+        "Wrote {} entries in {} bytes to {}",
+        show.entries.len(),
+        bytes.len(),
+        out.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,443 @@
+use std::fmt;
+
+use ledsrace_core::{
+    animation::Wipe,
+    color::Palette,
+    show::{
+        write_show, AnimationSpec, EntrySpec, ShowHeader, TransitionSpec, SHOW_MAGIC, SHOW_VERSION,
+    },
+    Color,
+};
+
+const DEFAULT_DURATION_MS: u32 = 10_000;
+
+/// Names of the animations a show can play
+pub const ANIMATIONS: &[&str] = &[
+    "sunset",
+    "static",
+    "sectors",
+    "raindrop",
+    "party",
+    "overtake",
+    "ghost-car",
+    "lightning",
+    "mexican-wave",
+    "unicorn",
+    "dutch-flag",
+    "pulse",
+    "growing-trail",
+];
+
+/// A parsed show, ready to be written as a blob
+pub struct Show {
+    pub header: ShowHeader,
+    pub entries: Vec<EntrySpec>,
+}
+
+impl Show {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut buf = vec![0; 64 + self.entries.len() * 64];
+        let len = write_show(&self.header, &self.entries, &mut buf).map_err(|e| e.to_string())?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+}
+
+/// A mistake in the show source
+#[derive(Debug, PartialEq)]
+pub struct Error {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Parse a show source. Every line holds one statement, a word starting with `#` starts a comment:
+///
+/// ```text
+/// duration 5s
+/// transition crossfade 800ms
+/// play growing-trail color=#ff1400 grow=60
+/// play dutch-flag for=finished repeat=2
+/// ```
+pub fn parse(source: &str) -> Result<Show, Error> {
+    let mut header = ShowHeader {
+        magic: SHOW_MAGIC,
+        version: SHOW_VERSION,
+        duration_ms: DEFAULT_DURATION_MS,
+        transition: TransitionSpec::Cut,
+        shuffle: None,
+        entries: 0,
+    };
+    let mut plays = Vec::new();
+
+    for (index, text) in source.lines().enumerate() {
+        let line = index + 1;
+        let error = |message: String| Error { line, message };
+
+        let mut words = text
+            .split_whitespace()
+            .take_while(|word| !word.starts_with('#'));
+        let Some(statement) = words.next() else {
+            continue;
+        };
+        let words: Vec<&str> = words.collect();
+
+        match (statement, words.as_slice()) {
+            ("duration", [duration]) => {
+                header.duration_ms = parse_duration(duration).map_err(error)?
+            }
+            ("transition", words) => header.transition = parse_transition(words).map_err(error)?,
+            ("shuffle", [seed]) => {
+                header.shuffle = Some(
+                    seed.parse()
+                        .map_err(|_| error(format!("invalid seed `{seed}`")))?,
+                )
+            }
+            ("play", [animation, params @ ..]) => plays.push((line, *animation, params.to_vec())),
+            ("duration" | "shuffle" | "play", _) => {
+                return Err(error(format!(
+                    "wrong number of arguments for `{statement}`"
+                )))
+            }
+            _ => return Err(error(format!("unknown statement `{statement}`"))),
+        }
+    }
+
+    // Entries use the show's duration, wherever it is set
+    let mut entries = Vec::new();
+    for (line, animation, params) in plays {
+        let error = |message: String| Error { line, message };
+        let too_long = || error("show has too many lines".into());
+
+        let mut params = Params::new(&params).map_err(error)?;
+        let animation = parse_animation(animation, &mut params).map_err(error)?;
+        let entry = EntrySpec {
+            line: line.try_into().map_err(|_| too_long())?,
+            animation,
+            duration_ms: match params.take("for") {
+                Some("finished") => None,
+                Some(duration) => Some(parse_duration(duration).map_err(error)?),
+                None => Some(header.duration_ms),
+            },
+            repeat: params.number("repeat", 1).map_err(error)?,
+            weight: params.number("weight", 1).map_err(error)?,
+        };
+        params.finish().map_err(error)?;
+        entry.validate().map_err(|e| error(e.kind.to_string()))?;
+        entries.push(entry);
+    }
+
+    header.entries = entries.len().try_into().map_err(|_| Error {
+        line: 0,
+        message: "too many entries".into(),
+    })?;
+    Ok(Show { header, entries })
+}
+
+fn parse_transition(words: &[&str]) -> Result<TransitionSpec, String> {
+    let transition = match words {
+        ["cut"] => TransitionSpec::Cut,
+        ["crossfade", duration] => TransitionSpec::Crossfade {
+            duration_ms: parse_duration(duration)?,
+        },
+        ["fade", duration] => TransitionSpec::FadeThroughBlack {
+            duration_ms: parse_duration(duration)?,
+        },
+        ["wipe", direction, duration] => TransitionSpec::Wipe {
+            wipe: match *direction {
+                "track" => Wipe::TrackOrder,
+                "left-to-right" => Wipe::LeftToRight,
+                "right-to-left" => Wipe::RightToLeft,
+                "bottom-to-top" => Wipe::BottomToTop,
+                "top-to-bottom" => Wipe::TopToBottom,
+                _ => return Err(format!("unknown wipe direction `{direction}`")),
+            },
+            duration_ms: parse_duration(duration)?,
+        },
+        _ => {
+            return Err(
+                "expected `cut`, `crossfade <duration>`, `fade <duration>` or \
+                 `wipe <direction> <duration>`"
+                    .into(),
+            )
+        }
+    };
+    Ok(transition)
+}
+
+/// Animation parameters with the defaults the firmware used before shows
+fn parse_animation(name: &str, params: &mut Params) -> Result<AnimationSpec, String> {
+    let animation = match name {
+        "sunset" => AnimationSpec::Sunset,
+        "static" => AnimationSpec::Static {
+            color: params.color("color", Color::ORANGE)?,
+        },
+        "sectors" => AnimationSpec::Sectors {
+            colors: params.colors("colors", palette_colors(Palette::DUTCH_FLAG))?,
+        },
+        "raindrop" => AnimationSpec::RainDrop {
+            duration_ms: params.duration("rain", 0)?,
+        },
+        "party" => AnimationSpec::Party {
+            duration_ms: params.duration("party", 0)?,
+        },
+        "overtake" => AnimationSpec::Overtake {
+            fast_speed: params.number("fast", 100.0)?,
+            slow_speed: params.number("slow", 50.0)?,
+        },
+        "ghost-car" => AnimationSpec::GhostCar {
+            speed: params.number("speed", 6.0)?,
+            length: params.number("length", 8)?,
+            color: params.color("color", Color::ORANGE)?,
+        },
+        "lightning" => AnimationSpec::Lightning {
+            color: params.color("color", Color::ORANGE)?,
+            speed: params.number("speed", 50.0)?,
+        },
+        "mexican-wave" => AnimationSpec::MexicanWave {
+            speed: params.number("speed", 0.5)?,
+            width: params.number("width", 20.0)?,
+            color: params.color("color", Color::ORANGE)?,
+        },
+        "unicorn" => AnimationSpec::Unicorn {
+            speed: params.number("speed", 0.2)?,
+            wavelength: params.number("wavelength", 60.0)?,
+        },
+        "dutch-flag" => AnimationSpec::DutchFlag,
+        "pulse" => AnimationSpec::Pulse {
+            speed: params.number("speed", 30.0)?,
+            width: params.number("width", 10.0)?,
+            colors: params.colors("colors", palette_colors(Palette::KINGSDAY))?,
+            interval_ms: match params.take("interval") {
+                Some("once") => None,
+                Some(interval) => Some(parse_duration(interval)?),
+                None => Some(5000),
+            },
+            random: params.number("random", true)?,
+        },
+        "growing-trail" => AnimationSpec::GrowingTrail {
+            color: params.color("color", Color::ORANGE)?,
+            speed: params.number("speed", 150.0)?,
+            grow_speed: params.number("grow", 60.0)?,
+        },
+        _ => {
+            return Err(format!(
+                "unknown animation `{name}`, expected one of {}",
+                ANIMATIONS.join(", ")
+            ))
+        }
+    };
+    Ok(animation)
+}
+
+/// `key=value` parameters of a line, every parameter has to be used once
+struct Params<'a> {
+    values: Vec<(&'a str, &'a str, bool)>,
+}
+
+impl<'a> Params<'a> {
+    fn new(words: &[&'a str]) -> Result<Self, String> {
+        let mut values: Vec<(&str, &str, bool)> = Vec::new();
+        for word in words {
+            let (key, value) = word
+                .split_once('=')
+                .ok_or_else(|| format!("expected `key=value`, found `{word}`"))?;
+            if values.iter().any(|(k, _, _)| *k == key) {
+                return Err(format!("`{key}` is set twice"));
+            }
+            values.push((key, value, false));
+        }
+        Ok(Self { values })
+    }
+
+    fn take(&mut self, key: &str) -> Option<&'a str> {
+        let (_, value, used) = self.values.iter_mut().find(|(k, _, _)| *k == key)?;
+        *used = true;
+        Some(*value)
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, key: &str, default: T) -> Result<T, String> {
+        match self.take(key) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("invalid value for `{key}`: `{value}`")),
+            None => Ok(default),
+        }
+    }
+
+    fn duration(&mut self, key: &str, default: u32) -> Result<u32, String> {
+        self.take(key).map_or(Ok(default), parse_duration)
+    }
+
+    fn color(&mut self, key: &str, default: Color) -> Result<Color, String> {
+        self.take(key).map_or(Ok(default), parse_color)
+    }
+
+    fn colors(&mut self, key: &str, default: [Color; 3]) -> Result<[Color; 3], String> {
+        let Some(value) = self.take(key) else {
+            return Ok(default);
+        };
+        let colors = value
+            .split(',')
+            .map(parse_color)
+            .collect::<Result<Vec<_>, _>>()?;
+        colors
+            .try_into()
+            .map_err(|_| format!("expected three colors for `{key}`"))
+    }
+
+    /// Fails on parameters the animation doesn't have
+    fn finish(self) -> Result<(), String> {
+        match self.values.iter().find(|(_, _, used)| !used) {
+            Some((key, _, _)) => Err(format!("unknown parameter `{key}`")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The first three colors of a palette, the default of a `colors` parameter
+fn palette_colors(palette: Palette) -> [Color; 3] {
+    std::array::from_fn(|i| palette.color(i))
+}
+
+/// Parse `500ms`, `5s` or `1.5s` into milliseconds
+fn parse_duration(text: &str) -> Result<u32, String> {
+    let invalid = || format!("invalid duration `{text}`, expected e.g. `500ms` or `5s`");
+    let ms = if let Some(ms) = text.strip_suffix("ms") {
+        ms.parse::<f32>().map_err(|_| invalid())?
+    } else if let Some(s) = text.strip_suffix('s') {
+        s.parse::<f32>().map_err(|_| invalid())? * 1000.0
+    } else {
+        return Err(invalid());
+    };
+    if !(0.0..=u32::MAX as f32).contains(&ms) {
+        return Err(invalid());
+    }
+    Ok(ms.round() as u32)
+}
+
+/// Parse `#rrggbb`
+fn parse_color(text: &str) -> Result<Color, String> {
+    let invalid = || format!("invalid color `{text}`, expected e.g. `#ff1400`");
+    let hex = text
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.is_ascii())
+        .ok_or_else(invalid)?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+    Ok(Color(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_show() {
+        let show = parse(
+            "# Kingsday\n\
+             duration 5s\n\
+             transition wipe left-to-right 1.5s\n\
+             \n\
+             play static color=#ff1400 repeat=2 # orange\n\
+             play pulse interval=once for=finished\n",
+        )
+        .unwrap();
+
+        assert_eq!(show.header.duration_ms, 5000);
+        assert_eq!(
+            show.header.transition,
+            TransitionSpec::Wipe {
+                wipe: Wipe::LeftToRight,
+                duration_ms: 1500
+            }
+        );
+        assert_eq!(show.header.entries, 2);
+        assert_eq!(show.entries[0].line, 5);
+        assert_eq!(show.entries[0].duration_ms, Some(5000));
+        assert_eq!(show.entries[0].repeat, 2);
+        assert_eq!(
+            show.entries[1].animation,
+            AnimationSpec::Pulse {
+                speed: 30.0,
+                width: 10.0,
+                colors: palette_colors(Palette::KINGSDAY),
+                interval_ms: None,
+                random: true,
+            }
+        );
+        assert_eq!(show.entries[1].duration_ms, None);
+        assert!(show.to_bytes().is_ok());
+    }
+
+    #[test]
+    fn speeds() {
+        let show = parse(
+            "play overtake fast=80 slow=40\n\
+             play lightning\n\
+             play growing-trail speed=100 grow=20\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            show.entries[0].animation,
+            AnimationSpec::Overtake {
+                fast_speed: 80.0,
+                slow_speed: 40.0,
+            }
+        );
+        assert_eq!(
+            show.entries[1].animation,
+            AnimationSpec::Lightning {
+                color: Color::ORANGE,
+                speed: 50.0,
+            }
+        );
+        assert_eq!(
+            show.entries[2].animation,
+            AnimationSpec::GrowingTrail {
+                color: Color::ORANGE,
+                speed: 100.0,
+                grow_speed: 20.0,
+            }
+        );
+        assert_eq!(
+            parse("play overtake slow=0").err().unwrap().to_string(),
+            "line 1: slow speed must be above zero"
+        );
+        assert_eq!(
+            parse("play static\nplay overtake fast=40 slow=40")
+                .err()
+                .unwrap()
+                .to_string(),
+            "line 2: fast speed must be above slow speed"
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let error = |source| parse(source).err().unwrap().to_string();
+
+        assert_eq!(
+            error("play static\nplay ghost-car colour=#ffffff"),
+            "line 2: unknown parameter `colour`"
+        );
+        assert_eq!(
+            error("duration 5s\n\nplay ghost-car speed=0"),
+            "line 3: speed must be above zero"
+        );
+        assert_eq!(
+            error("play static color=orange"),
+            "line 1: invalid color `orange`, expected e.g. `#ff1400`"
+        );
+        assert_eq!(
+            error("play static color=#aéaaa"),
+            "line 1: invalid color `#aéaaa`, expected e.g. `#ff1400`"
+        );
+        assert_eq!(error("loop"), "line 1: unknown statement `loop`");
+    }
+}
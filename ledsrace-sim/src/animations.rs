@@ -0,0 +1,126 @@
+use embassy_time::Duration;
+use ledsrace_core::{animation::*, color::Palette, Circuit, Color, Priority};
+
+/// Names accepted by `--animation`
+pub const NAMES: &[&str] = &[
+    "sunset",
+    "static",
+    "sectors",
+    "sector-frames",
+    "raindrop",
+    "party",
+    "overtake",
+    "ghost-car",
+    "lightning",
+    "mexican-wave",
+    "unicorn",
+    "dutch-flag",
+    "pulse",
+    "growing-trail",
+    "race-playback",
+    "race-over-sunset",
+];
+
+/// The 2024 Dutch Grand Prix, recorded for the 20x20 board
+static RACE: &[u8] = include_bytes!("../../ledsrace-core/races/zandvoort_2024_20x20_5hz.bin");
+
+/// Opacity of the sunset behind the cars in `race-over-sunset`
+const RACE_BACKGROUND_OPACITY: u8 = 40;
+
+/// What `--animation` plays, a single animation or several as layers
+#[allow(clippy::large_enum_variant)] // Only one is created per run
+pub enum Preview {
+    Single(Animations),
+    Layered(Compositor<2>),
+}
+
+impl Animation for Preview {
+    fn update<const N: usize, C: Circuit<N>>(&mut self, circuit: &C, dt: Duration) {
+        match self {
+            Preview::Single(animation) => animation.update(circuit, dt),
+            Preview::Layered(compositor) => compositor.update(circuit, dt),
+        }
+    }
+
+    fn render<const N: usize, C: Circuit<N>>(&self, circuit: &mut C) {
+        match self {
+            Preview::Single(animation) => animation.render(circuit),
+            Preview::Layered(compositor) => compositor.render(circuit),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        match self {
+            Preview::Single(animation) => animation.is_finished(),
+            Preview::Layered(compositor) => compositor.is_finished(),
+        }
+    }
+
+    fn priority(&self) -> Priority {
+        match self {
+            Preview::Single(animation) => animation.priority(),
+            Preview::Layered(compositor) => compositor.priority(),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Preview::Single(animation) => animation.reset(),
+            Preview::Layered(compositor) => compositor.reset(),
+        }
+    }
+}
+
+fn race_playback() -> Animations {
+    Animations::RacePlayback(RacePlayback::new(RACE, Duration::from_millis(50), |n| {
+        Some(Palette::TEAMS_2024.color(n as usize))
+    }))
+}
+
+/// Create an animation by name, with the parameters the firmware uses where it runs them
+pub fn by_name(name: &str, led_count: usize) -> Option<Preview> {
+    let animation = match name {
+        "sunset" => Animations::Sunset(SunsetGlow::new()),
+        "static" => Animations::Static(StaticColor::new(Color::ORANGE)),
+        "sectors" => {
+            Animations::ShowSectors(ShowSectors::new(Color::RED, Color::WHITE, Color::BLUE))
+        }
+        "sector-frames" => {
+            let mut frames = SectorFrames::new(Duration::from_millis(500));
+            frames.add_frame([Color::RED, Color::WHITE, Color::BLUE]);
+            frames.add_frame([Color::BLUE, Color::RED, Color::WHITE]);
+            frames.add_frame([Color::WHITE, Color::BLUE, Color::RED]);
+            Animations::SectorFrames(frames)
+        }
+        "raindrop" => Animations::RainDrop(RainDropRace::new(Duration::from_millis(0))),
+        "party" => Animations::RainDrop(RainDropRace::party(Duration::from_millis(0))),
+        "overtake" => Animations::OvertakeDuel(OvertakeDuel::new(led_count)),
+        "ghost-car" => Animations::GhostCar(GhostCar::new(6.0, 8, Color::ORANGE)),
+        "lightning" => Animations::LightningSprint(LightningSprint::new(Color::ORANGE)),
+        "mexican-wave" => Animations::MexicanWave(MexicanWave::new(0.5, 20.0, Color::ORANGE)),
+        "unicorn" => Animations::UnicornRainbow(UnicornRainbow::new(0.2, 60.0)),
+        "dutch-flag" => Animations::DutchFlag(DutchFlag::new()),
+        "pulse" => Animations::CircuitPulse(CircuitPulse::new(
+            30.0,
+            10.0,
+            Palette::KINGSDAY,
+            Some(Duration::from_millis(5000)),
+            true,
+        )),
+        "growing-trail" => Animations::GrowingTrail(GrowingTrail::new(Color::ORANGE, 60.0)),
+        "race-playback" => race_playback(),
+        "race-over-sunset" => {
+            // Cars on top of a dim sunset, the cars cover the background where they are
+            let mut compositor = Compositor::new();
+            compositor.add_layer(
+                Layer::new(Animations::Sunset(SunsetGlow::new()))
+                    .with_blend(BlendMode::Replace)
+                    .with_opacity(RACE_BACKGROUND_OPACITY),
+            );
+            compositor.add_layer(Layer::new(race_playback()));
+            return Some(Preview::Layered(compositor));
+        }
+        _ => return None,
+    };
+    Some(Preview::Single(animation))
+}
@@ -0,0 +1,155 @@
+use std::io::{self, Write};
+
+use ledsrace_core::{Color, Point};
+
+/// Color drawn around every LED, so LEDs that are off still show the layout
+const OUTLINE: Color = Color(40, 40, 40);
+
+/// RGB image of the circuit with every LED drawn as a dot at its position
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+    min_x: f32,
+    max_y: f32,
+    /// Pixels per circuit unit
+    scale: f32,
+    /// Radius of an LED dot in pixels
+    led_radius: f32,
+}
+
+impl Canvas {
+    pub fn new(positions: &[Point], scale: f32) -> Self {
+        let min_x = positions.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+        let max_x = positions
+            .iter()
+            .map(|p| p.x)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let min_y = positions.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_y = positions
+            .iter()
+            .map(|p| p.y)
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        // LEDs are about 3 units apart, leave a small gap between the dots
+        let led_radius = (1.3 * scale).max(1.0);
+        let margin = led_radius * 2.0;
+
+        let mut canvas = Self {
+            width: ((max_x - min_x) * scale + 2.0 * margin).ceil() as usize,
+            height: ((max_y - min_y) * scale + 2.0 * margin).ceil() as usize,
+            pixels: Vec::new(),
+            min_x: min_x - margin / scale,
+            max_y: max_y + margin / scale,
+            scale,
+            led_radius,
+        };
+        canvas.pixels = vec![0; canvas.width * canvas.height * 3];
+        canvas
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixel coordinates of a point. The circuit's y axis points up, the image's points down.
+    pub fn to_pixel(&self, point: &Point) -> (f32, f32) {
+        (
+            (point.x - self.min_x) * self.scale,
+            (self.max_y - point.y) * self.scale,
+        )
+    }
+
+    /// Draw a frame, `colors` holds one color per position
+    pub fn draw(&mut self, positions: &[Point], colors: impl IntoIterator<Item = Color>) {
+        self.pixels.fill(0);
+        for (point, color) in positions.iter().zip(colors) {
+            let (x, y) = self.to_pixel(point);
+            self.fill_circle(x, y, self.led_radius + 1.0, OUTLINE);
+            self.fill_circle(x, y, self.led_radius, color);
+        }
+    }
+
+    fn fill_circle(&mut self, cx: f32, cy: f32, radius: f32, color: Color) {
+        let x_start = (cx - radius).floor().max(0.0) as usize;
+        let x_end = ((cx + radius).ceil() as usize).min(self.width);
+        let y_start = (cy - radius).floor().max(0.0) as usize;
+        let y_end = ((cy + radius).ceil() as usize).min(self.height);
+
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                let dx = x as f32 + 0.5 - cx;
+                let dy = y as f32 + 0.5 - cy;
+                if dx * dx + dy * dy <= radius * radius {
+                    let i = (y * self.width + x) * 3;
+                    self.pixels[i..i + 3].copy_from_slice(&[color.0, color.1, color.2]);
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        let i = (y * self.width + x) * 3;
+        Color(self.pixels[i], self.pixels[i + 1], self.pixels[i + 2])
+    }
+
+    /// Write the image as binary PPM (P6)
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels)
+    }
+
+    pub fn write_png(&self, out: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leds_drawn_at_their_position() {
+        let positions = [Point::new(0.0, 0.0), Point::new(10.0, 5.0)];
+        let mut canvas = Canvas::new(&positions, 2.0);
+        canvas.draw(&positions, [Color(255, 0, 0), Color(0, 0, 255)]);
+
+        // Lower left LED ends up at the bottom of the image, upper right one at the top
+        let (x, y) = canvas.to_pixel(&positions[0]);
+        assert!(y > canvas.height() as f32 / 2.0);
+        assert_eq!(canvas.pixel(x as usize, y as usize), Color(255, 0, 0));
+
+        let (x, y) = canvas.to_pixel(&positions[1]);
+        assert!(y < canvas.height() as f32 / 2.0);
+        assert_eq!(canvas.pixel(x as usize, y as usize), Color(0, 0, 255));
+
+        // Background stays black
+        assert_eq!(canvas.pixel(0, 0), Color(0, 0, 0));
+    }
+
+    #[test]
+    fn ppm_header() {
+        let positions = [Point::new(0.0, 0.0)];
+        let canvas = Canvas::new(&positions, 1.0);
+        let mut out = Vec::new();
+        canvas.write_ppm(&mut out).unwrap();
+
+        let header = format!("P6\n{} {}\n255\n", canvas.width(), canvas.height());
+        assert!(out.starts_with(header.as_bytes()));
+        assert_eq!(
+            out.len(),
+            header.len() + canvas.width() * canvas.height() * 3
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Renders animations on the host, to review them without flashing the board.
+//!
+//! Every frame is written as a separate image, e.g. to turn them into a GIF:
+//!
+//! ```bash
+//! cargo run -- --animation ghost-car --seconds 5 --out frames
+//! ffmpeg -framerate 25 -i frames/ghost-car_%05d.png ghost-car.gif
+//! ```
+//!
+//! With `--live` the circuit is drawn in the terminal instead, which needs a terminal with 24-bit
+//! color support.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+    thread,
+    time::Instant,
+};
+
+use embassy_time::Duration;
+use ledsrace_core::{animation::Animation, zandvoort::Zandvoort, Circuit};
+
+mod animations;
+mod image;
+mod terminal;
+
+use image::Canvas;
+use terminal::TerminalView;
+
+const LED_COUNT: usize = 216;
+
+const USAGE: &str = "\
+Usage: ledsrace-sim --animation <name> [options]
+
+Options:
+  --animation <name>  Animation to render, see --list
+  --seconds <s>       Length of the rendered animation [default: 5, forever with --live]
+  --fps <n>           Frames per second of the virtual clock [default: 25]
+  --out <dir>         Directory to write the frames to [default: frames]
+  --format <fmt>      Image format, png or ppm [default: png]
+  --scale <n>         Pixels per circuit unit [default: 4]
+  --live              Draw the frames in the terminal in real time, until the animation
+                      finishes or --seconds have passed
+  --columns <n>       Width of the terminal drawing in characters [default: 100]
+  --list              List the available animations";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Png,
+    Ppm,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Ppm => "ppm",
+        }
+    }
+}
+
+struct Options {
+    animation: String,
+    /// `None` renders 5 seconds to files, or runs forever in the terminal
+    seconds: Option<f32>,
+    fps: u32,
+    out: PathBuf,
+    format: Format,
+    scale: f32,
+    live: bool,
+    columns: usize,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        animation: String::new(),
+        seconds: None,
+        fps: 25,
+        out: PathBuf::from("frames"),
+        format: Format::Png,
+        scale: 4.0,
+        live: false,
+        columns: 100,
+    };
+
+    while let Some(arg) = args.next() {
+        if arg == "--list" {
+            return Ok(None);
+        }
+        if arg == "--live" {
+            options.live = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {arg}"))?;
+        let invalid = || format!("Invalid value for {arg}: {value}");
+        match arg.as_str() {
+            "--animation" => options.animation = value,
+            "--seconds" => options.seconds = Some(value.parse().map_err(|_| invalid())?),
+            "--fps" => options.fps = value.parse().map_err(|_| invalid())?,
+            "--out" => options.out = PathBuf::from(value),
+            "--scale" => options.scale = value.parse().map_err(|_| invalid())?,
+            "--columns" => options.columns = value.parse().map_err(|_| invalid())?,
+            "--format" => {
+                options.format = match value.as_str() {
+                    "png" => Format::Png,
+                    "ppm" => Format::Ppm,
+                    _ => return Err(format!("Unknown format: {value}")),
+                }
+            }
+            _ => return Err(format!("Unknown option: {arg}")),
+        }
+    }
+
+    if options.animation.is_empty() {
+        return Err("No animation given".into());
+    }
+    if options.fps == 0 {
+        return Err("--fps must be at least 1".into());
+    }
+    Ok(Some(options))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            for name in animations::NAMES {
+                println!("{name}");
+            }
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if options.live {
+        return match run_live(&options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("{error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    match run(&options) {
+        Ok((frames, (width, height))) => {
+            println!(
+                "Wrote {frames} frames of {width}x{height} to {}",
+                options
+                    .out
+                    .join(format!("{}_*", options.animation))
+                    .display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Render the frames, returns the number of frames written and their size in pixels
+fn run(options: &Options) -> Result<(u32, (usize, usize)), Box<dyn std::error::Error>> {
+    let mut animation = animations::by_name(&options.animation, LED_COUNT)
+        .ok_or_else(|| format!("Unknown animation: {}", options.animation))?;
+
+    let mut zandvoort: Zandvoort<LED_COUNT> = Zandvoort::new();
+    let positions = zandvoort.led_positions();
+    let mut canvas = Canvas::new(positions, options.scale);
+
+    std::fs::create_dir_all(&options.out)?;
+
+    let size = (canvas.width(), canvas.height());
+    let frame_time = Duration::from_micros(1_000_000 / options.fps as u64);
+    let frames = (options.seconds.unwrap_or(5.0) * options.fps as f32).round() as u32;
+
+    for frame in 0..frames {
+        // Virtual clock, time only advances per frame
+        let dt = if frame == 0 {
+            Duration::from_ticks(0)
+        } else {
+            frame_time
+        };
+
+        zandvoort.led_buffer().clear();
+        animation.update(&zandvoort, dt);
+        animation.render(&mut zandvoort);
+        let colors = zandvoort.led_buffer().get_colors().iter().map(|(c, _)| *c);
+        canvas.draw(positions, colors);
+
+        let path = options.out.join(format!(
+            "{}_{frame:05}.{}",
+            options.animation,
+            options.format.extension()
+        ));
+        let file = BufWriter::new(File::create(path)?);
+        match options.format {
+            Format::Png => canvas.write_png(file)?,
+            Format::Ppm => canvas.write_ppm(file)?,
+        }
+
+        if animation.is_finished() {
+            return Ok((frame + 1, size));
+        }
+    }
+
+    Ok((frames, size))
+}
+
+/// Draw the frames in the terminal, paced by the wall clock
+fn run_live(options: &Options) -> Result<(), Box<dyn std::error::Error>> {
+    let mut animation = animations::by_name(&options.animation, LED_COUNT)
+        .ok_or_else(|| format!("Unknown animation: {}", options.animation))?;
+
+    let mut zandvoort: Zandvoort<LED_COUNT> = Zandvoort::new();
+    let mut view = TerminalView::new(zandvoort.led_positions(), options.columns);
+
+    let frame_time = Duration::from_micros(1_000_000 / options.fps as u64);
+    let frames = options
+        .seconds
+        .map(|seconds| (seconds * options.fps as f32).round() as u32);
+
+    let mut stdout = io::stdout().lock();
+    // Clear the screen once, every frame is drawn over the previous one
+    write!(stdout, "\x1b[2J")?;
+
+    let start = Instant::now();
+    let mut frame = 0;
+    while frames.is_none_or(|frames| frame < frames) {
+        let timestamp = frame_time * frame;
+        let dt = if frame == 0 {
+            Duration::from_ticks(0)
+        } else {
+            frame_time
+        };
+
+        zandvoort.led_buffer().clear();
+        animation.update(&zandvoort, dt);
+        animation.render(&mut zandvoort);
+        let colors = zandvoort.led_buffer().get_colors().iter().map(|(c, _)| *c);
+        stdout.write_all(view.draw(colors).as_bytes())?;
+        writeln!(
+            stdout,
+            "{} {:>6.2}s",
+            options.animation,
+            timestamp.as_micros() as f32 * 1e-6
+        )?;
+        stdout.flush()?;
+
+        if animation.is_finished() {
+            break;
+        }
+
+        frame += 1;
+        // Sleep until the next frame is due, skip the sleep when running behind
+        let next = start + std::time::Duration::from_micros((frame_time * frame).as_micros());
+        thread::sleep(next.saturating_duration_since(Instant::now()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::fmt::Write;
+
+use ledsrace_core::{Color, Point};
+
+/// Color of LEDs that are off, so the layout stays visible
+const OFF: Color = Color(40, 40, 40);
+
+/// Draws the circuit in a terminal using 24-bit ANSI colors.
+///
+/// Every character cell holds two pixels stacked vertically using the upper half block, with the
+/// top pixel as foreground and the bottom pixel as background color. This makes the pixels about
+/// square in most terminal fonts.
+pub struct TerminalView {
+    columns: usize,
+    rows: usize,
+    /// Pixel of every LED
+    led_pixels: Vec<(usize, usize)>,
+    pixels: Vec<Option<Color>>,
+    frame: String,
+}
+
+impl TerminalView {
+    /// Scale the circuit to be `columns` characters wide
+    pub fn new(positions: &[Point], columns: usize) -> Self {
+        let min_x = positions.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+        let max_x = positions
+            .iter()
+            .map(|p| p.x)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let min_y = positions.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_y = positions
+            .iter()
+            .map(|p| p.y)
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        let columns = columns.max(1);
+        let scale = (columns - 1) as f32 / (max_x - min_x).max(f32::EPSILON);
+        let height = ((max_y - min_y) * scale).round() as usize + 1;
+        let rows = height.div_ceil(2);
+
+        // The circuit's y axis points up, the terminal's points down
+        let led_pixels = positions
+            .iter()
+            .map(|p| {
+                (
+                    ((p.x - min_x) * scale).round() as usize,
+                    ((max_y - p.y) * scale).round() as usize,
+                )
+            })
+            .collect();
+
+        Self {
+            columns,
+            rows,
+            led_pixels,
+            pixels: vec![None; columns * rows * 2],
+            frame: String::new(),
+        }
+    }
+
+    /// Escape sequences that draw a frame, starting at the top left of the terminal.
+    /// `colors` holds one color per position.
+    pub fn draw(&mut self, colors: impl IntoIterator<Item = Color>) -> &str {
+        self.pixels.fill(None);
+        for (&(x, y), color) in self.led_pixels.iter().zip(colors) {
+            let color = if color == Color(0, 0, 0) { OFF } else { color };
+            let pixel = &mut self.pixels[y * self.columns + x];
+            // LEDs that end up on the same pixel show the brightest one
+            if pixel.is_none_or(|p| brightness(p) < brightness(color)) {
+                *pixel = Some(color);
+            }
+        }
+
+        self.frame.clear();
+        self.frame.push_str("\x1b[H");
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let top = self.pixels[row * 2 * self.columns + column];
+                let bottom = self.pixels[(row * 2 + 1) * self.columns + column];
+                let (glyph, foreground, background) = match (top, bottom) {
+                    (None, None) => {
+                        self.frame.push(' ');
+                        continue;
+                    }
+                    (Some(top), bottom) => ('▀', top, bottom),
+                    (None, Some(bottom)) => ('▄', bottom, None),
+                };
+
+                let Color(r, g, b) = foreground;
+                let _ = write!(self.frame, "\x1b[38;2;{r};{g};{b}m");
+                if let Some(Color(r, g, b)) = background {
+                    let _ = write!(self.frame, "\x1b[48;2;{r};{g};{b}m");
+                }
+                self.frame.push(glyph);
+                self.frame.push_str("\x1b[0m");
+            }
+            self.frame.push('\n');
+        }
+        &self.frame
+    }
+}
+
+fn brightness(Color(r, g, b): Color) -> u16 {
+    r as u16 + g as u16 + b as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_leds_as_half_blocks() {
+        // Two LEDs stacked in the first column, one at the top of the second
+        let positions = [
+            Point::new(0.0, 1.0),
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+        ];
+        let mut view = TerminalView::new(&positions, 2);
+
+        let frame = view.draw([Color(255, 0, 0), Color(0, 0, 0), Color(0, 0, 255)]);
+        assert_eq!(
+            frame,
+            concat!(
+                "\x1b[H",
+                "\x1b[38;2;255;0;0m\x1b[48;2;40;40;40m▀\x1b[0m",
+                "\x1b[38;2;0;0;255m▀\x1b[0m",
+                "\n"
+            )
+        );
+    }
+}